@@ -5,9 +5,11 @@
 // Implemented for compatibility with use_symbols in the main crate
 macro_rules! path_from_id {
     ($id_name:ident) => {{
+        let dir = format!("{}/rustifact", std::env::var("OUT_DIR").unwrap());
+        std::fs::create_dir_all(&dir).unwrap();
         format!(
             "{}/rustifact_{}_{}.rs",
-            std::env::var("OUT_DIR").unwrap(),
+            dir,
             std::env::var("CARGO_PKG_NAME").unwrap(),
             stringify!($id_name),
         )
@@ -66,6 +68,23 @@ fn public_entry_for(dim: usize, const_static: &str, params_extra: &str) -> Strin
     )
 }
 
+fn public_base_len_entry_for(id: &str) -> String {
+    format!(
+        "($id:ident, $t:ty, $data:expr, with_len) => {{ rustifact::{}!($id, $t : 1, $data, with_len); }};",
+        id
+    )
+}
+
+fn public_len_entry_for(dim: usize, const_static: &str, params_extra: &str) -> String {
+    format!(
+        "    ($id:ident, $t:ty : {}, $data:expr, with_len) => {{ rustifact::__write_with!({}, {}, $id, $t, $data, {}, with_len) }};",
+        dim,
+        dim,
+        const_static,
+        params_extra
+    )
+}
+
 macro_rules! write_public {
     ($id:ident, $const_static:ident, $params_extra:expr, $doc:expr) => {
         let path_str = path_from_id!($id);
@@ -73,15 +92,21 @@ macro_rules! write_public {
         let id = stringify!($id);
         let const_static = stringify!($const_static);
         let s = format!(
-            "#[doc = \"{}\"]\n{} {} {{\n{}\n{}\n}}",
+            "#[doc = \"{}\"]\n{} {} {{\n{}\n{}\n{}\n{}\n}}",
             $doc,
             MACRO_HEADER,
             id,
             public_base_entry_for(id),
+            public_base_len_entry_for(id),
             (1..=NUM_DIMS)
                 .into_iter()
                 .map(|d| public_entry_for(d, const_static, $params_extra))
                 .collect::<Vec<String>>()
+                .join("\n"),
+            (1..=NUM_DIMS)
+                .into_iter()
+                .map(|d| public_len_entry_for(d, const_static, $params_extra))
+                .collect::<Vec<String>>()
                 .join("\n")
         );
         std::fs::write(&path, s).unwrap();
@@ -143,7 +168,11 @@ Makes the array, array reference, or array slice available for import into the m
 * `$id`: the name/identifier to give the exported array
 * `$t`: the type of elements of the exported array will contain. Optionally followed by `: DIM`
 where `DIM` is the dimension (1, 2, 3, ...) of the array. The dimension defaults to 1 when unspecified.
+May be `_` to infer the type from the elements, for types that support this (see `ToTokenStream::type_toks`).
 * `$data`: the contents of the array. May be an array, an array reference, or array slice.
+* `with_len` (optional): also write the constant `<$id>_LEN`, of type `usize`, holding the length of the
+array (the outermost length, for a multidimensional array). This is imported along with the array, and is
+usable in const contexts, such as the length of another array.
 
 ## Further notes
 * Must be called from a build script (build.rs) only."#
@@ -161,7 +190,11 @@ Stack allocated types (such as [`slice`]s and [`array`]s) may be returned.
 * `$id`: the name/identifier to give the exported array
 * `$t`: the type of elements of the exported array will contain. Optionally followed by `: DIM`
 where `DIM` is the dimension (1, 2, 3, ...) of the array. The dimension defaults to 1 when unspecified.
+May be `_` to infer the type from the elements, for types that support this (see `ToTokenStream::type_toks`).
 * `$data`: the contents of the array. May be an array, an array reference, or array slice.
+* `with_len` (optional): also write the constant `<$id>_LEN`, of type `usize`, holding the length of the
+array (the outermost length, for a multidimensional array). This is imported along with the array, and is
+usable in const contexts, such as the length of another array.
 
 ## Further notes
 * Must be called from a build script (build.rs) only.
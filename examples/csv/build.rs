@@ -0,0 +1,19 @@
+use rustifact::ToTokenStream;
+
+fn parse_row(row: Vec<String>) -> (String, u32) {
+    let population = row[1].parse().expect("Invalid population");
+    (row[0].clone(), population)
+}
+
+fn main() {
+    //
+    // Load the rows of the CSV file. This also tells Cargo to rerun the build script
+    // whenever the file changes, so the exported data never goes stale.
+    //
+    let rows = rustifact::load_csv("data/cities.csv").expect("Couldn't load the city data");
+    //
+    // The first row is a header, so we skip it.
+    //
+    let city_data: Vec<(String, u32)> = rows.into_iter().skip(1).map(parse_row).collect();
+    rustifact::write_static_array!(CITY_DATA, (&'static str, u32), &city_data);
+}
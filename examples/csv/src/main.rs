@@ -0,0 +1,7 @@
+rustifact::use_symbols!(CITY_DATA);
+
+fn main() {
+    for (name, population) in CITY_DATA.iter() {
+        println!("{} has population {}", name, population)
+    }
+}
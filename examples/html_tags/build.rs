@@ -39,4 +39,5 @@ fn main() {
         ));
     }
     rustifact::write_statics!(public, HTML_TAGS, &'static str, &tags);
+    rustifact::write_mod!(private, tags, HTML_TAGS);
 }
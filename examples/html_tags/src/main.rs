@@ -1,7 +1,6 @@
-#[allow(dead_code)]
-mod tags {
-    rustifact::use_symbols!(HTML_TAGS);
-}
+#![allow(dead_code)]
+
+rustifact::use_symbols!(tags);
 
 fn main() {
     println!("{}", tags::OPEN_HTML);
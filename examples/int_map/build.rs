@@ -0,0 +1,14 @@
+use rustifact::{MapBuilder, ToTokenStream};
+
+fn main() {
+    //
+    // Integer keys work just like string keys. Here we map HTTP status codes to
+    // their reason phrases.
+    //
+    let mut status_map = MapBuilder::new();
+    status_map.entry(200u32, "OK");
+    status_map.entry(301, "Moved Permanently");
+    status_map.entry(404, "Not Found");
+    status_map.entry(500, "Internal Server Error");
+    rustifact::write_static!(STATUS_MAP, Map<u32, &'static str>, &status_map);
+}
@@ -0,0 +1,12 @@
+use rustifact::Map;
+
+rustifact::use_symbols!(STATUS_MAP);
+
+fn main() {
+    for code in [200, 301, 404, 418, 500] {
+        match STATUS_MAP.get(&code) {
+            Some(reason) => println!("{}: {}", code, reason),
+            None => println!("{}: unknown status", code),
+        }
+    }
+}
@@ -1,10 +1,11 @@
 use data::Record;
-use rustifact::{MapBuilder, ToTokenStream};
+use rustifact::ToTokenStream;
+use std::collections::BTreeMap;
 
 fn main() {
-    let mut map = MapBuilder::new();
-    map.entry("first", Record { n: 0, s: "abc" });
-    map.entry("second", Record { n: 1, s: "def" });
-    map.entry("third", Record { n: 2, s: "ghi" });
-    rustifact::write_static!(RECORD_MAP, Map<&'static str, Record>, &map);
+    let mut map = BTreeMap::new();
+    map.insert("first", Record { n: 0, s: "abc" });
+    map.insert("second", Record { n: 1, s: "def" });
+    map.insert("third", Record { n: 2, s: "ghi" });
+    rustifact::write_phf_map!(RECORD_MAP, Map<&'static str, Record>, &map);
 }
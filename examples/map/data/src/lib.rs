@@ -1,6 +1,6 @@
 use rustifact::ToTokenStream;
 
-#[derive(ToTokenStream, Debug)]
+#[derive(ToTokenStream, Clone, Debug)]
 pub struct Record {
     pub n: u32,
     pub s: &'static str,
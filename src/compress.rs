@@ -0,0 +1,109 @@
+// Raw DEFLATE (RFC 1951) via miniz_oxide, for baking compressed data at build time and decompressing it at
+// runtime.
+
+// The highest level miniz_oxide supports, as compression happens once, at build time.
+const LEVEL: u8 = 10;
+
+/// Compress `data` as a raw DEFLATE stream.
+pub fn deflate(data: &[u8]) -> Vec<u8> {
+    miniz_oxide::deflate::compress_to_vec(data, LEVEL)
+}
+
+/// Decompress a raw DEFLATE stream, whose decompressed length is `len`.
+pub fn inflate(data: &[u8], len: usize) -> Vec<u8> {
+    let out = match miniz_oxide::inflate::decompress_to_vec_with_limit(data, len) {
+        Ok(out) => out,
+        Err(e) => panic!("Couldn't decompress the data: {}", e),
+    };
+    if out.len() != len {
+        panic!(
+            "Couldn't decompress the data: expected {} bytes, but found {}",
+            len,
+            out.len()
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Deterministic pseudo-random bytes (xorshift), which don't compress.
+    fn random_bytes(len: usize, mut seed: u64) -> Vec<u8> {
+        (0..len)
+            .map(|_| {
+                seed ^= seed << 13;
+                seed ^= seed >> 7;
+                seed ^= seed << 17;
+                seed as u8
+            })
+            .collect()
+    }
+
+    fn round_trip(data: &[u8]) -> Vec<u8> {
+        let compressed = deflate(data);
+        assert!(inflate(&compressed, data.len()) == data);
+        compressed
+    }
+
+    #[test]
+    fn empty() {
+        let compressed = round_trip(&[]);
+        assert!(compressed.len() <= 5);
+    }
+
+    #[test]
+    fn incompressible() {
+        let data = random_bytes(200_000, 1);
+        let compressed = round_trip(&data);
+        // Falls back to stored blocks, which cost a few bytes apiece.
+        assert!(compressed.len() < data.len() + data.len() / 1000);
+    }
+
+    #[test]
+    fn long_back_references() {
+        // A chunk repeated at nearly the full window distance, past compressible filler.
+        let chunk = random_bytes(1000, 3);
+        let mut data = chunk.clone();
+        data.extend((0..32000 - 1000).map(|i| (i % 7) as u8));
+        data.extend(&chunk);
+        let without_repeat = deflate(&data[..data.len() - chunk.len()]);
+        let compressed = round_trip(&data);
+        assert!(compressed.len() < without_repeat.len() + 20);
+        // Runs much longer than the maximum match length.
+        let data = vec![7u8; 100_000];
+        let compressed = round_trip(&data);
+        assert!(compressed.len() < 1000);
+        let data: Vec<u8> = (0..300_000u32).map(|i| (i % 251) as u8).collect();
+        round_trip(&data);
+    }
+
+    #[test]
+    fn wrong_length() {
+        let compressed = deflate(b"rustifact");
+        for len in [8, 10] {
+            assert!(std::panic::catch_unwind(|| inflate(&compressed, len)).is_err());
+        }
+        assert!(std::panic::catch_unwind(|| inflate(&compressed[..3], 9)).is_err());
+    }
+
+    // Streams compressed by zlib, covering each block type.
+    #[test]
+    fn reference_streams() {
+        assert!(inflate(&[3, 0], 0).is_empty());
+        assert!(inflate(&[1, 3, 0, 252, 255, 97, 98, 99], 3) == b"abc");
+        let dynamic = [
+            85, 208, 203, 13, 131, 64, 12, 4, 208, 86, 40, 33, 44, 251, 241, 42, 213, 68, 145, 34,
+            113, 37, 164, 127, 56, 69, 126, 199, 145, 199, 243, 59, 126, 223, 115, 255, 188, 222,
+            231, 242, 120, 46, 199, 31, 172, 25, 212, 12, 38, 180, 158, 81, 105, 25, 109, 220, 42,
+            127, 29, 201, 192, 109, 67, 18, 98, 29, 25, 141, 2, 19, 13, 20, 59, 183, 105, 74, 236,
+            134, 35, 240, 215, 96, 78, 219, 57, 145, 235, 249, 7, 179, 225, 23, 132, 38, 102, 161,
+            121, 192, 108, 72, 22, 66, 135, 179, 59, 52, 14, 43, 183, 160, 94, 191, 209, 5,
+        ];
+        let expected: String = (0..40)
+            .map(|i| format!("rustifact {}; ", i * i % 97))
+            .collect();
+        assert!(inflate(&dynamic, expected.len()) == expected.as_bytes());
+    }
+}
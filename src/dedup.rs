@@ -0,0 +1,67 @@
+use proc_macro2::{Delimiter, Ident, Span, TokenStream, TokenTree};
+use quote::quote;
+use std::collections::HashMap;
+
+use crate::repeat::split_elements;
+
+// Elements whose tokens are shorter than this aren't worth interning.
+const MIN_INTERN_LEN: usize = 32;
+
+// Apply f to each element at the given depth of (possibly nested) array literals.
+fn map_leaves<F>(tokens: TokenStream, dim: usize, f: &mut F) -> TokenStream
+where
+    F: FnMut(TokenStream) -> TokenStream,
+{
+    if dim == 0 {
+        return f(tokens);
+    }
+    tokens
+        .into_iter()
+        .map(|tt| match tt {
+            TokenTree::Group(g) if g.delimiter() == Delimiter::Bracket => {
+                let mut inner = TokenStream::new();
+                for element in split_elements(g.stream()) {
+                    let element = map_leaves(element, dim - 1, f);
+                    inner.extend(quote! { #element, });
+                }
+                TokenTree::Group(proc_macro2::Group::new(Delimiter::Bracket, inner))
+            }
+            other => other,
+        })
+        .collect()
+}
+
+/// Replace repeated elements of an array literal of dimension `dim` with references to constants.
+///
+/// Elements of type `leaf_type` which occur more than once (and aren't trivially small) are emitted
+/// once each, as `const` bindings in a block which evaluates to the array.
+pub fn dedup_leaves(tokens: TokenStream, dim: usize, leaf_type: TokenStream) -> TokenStream {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    map_leaves(tokens.clone(), dim, &mut |leaf| {
+        let leaf_str = leaf.to_string();
+        if leaf_str.len() >= MIN_INTERN_LEN {
+            *counts.entry(leaf_str).or_insert(0) += 1;
+        }
+        leaf
+    });
+    let mut ids: HashMap<String, Ident> = HashMap::new();
+    let mut bindings = TokenStream::new();
+    let tokens = map_leaves(tokens, dim, &mut |leaf| {
+        let leaf_str = leaf.to_string();
+        if counts.get(&leaf_str).copied().unwrap_or(0) < 2 {
+            return leaf;
+        }
+        let num_ids = ids.len();
+        let id = ids.entry(leaf_str).or_insert_with(|| {
+            let id = Ident::new(&format!("__RUSTIFACT_{}", num_ids), Span::call_site());
+            bindings.extend(quote! { const #id: #leaf_type = #leaf; });
+            id
+        });
+        quote! { #id }
+    });
+    if bindings.is_empty() {
+        tokens
+    } else {
+        quote! { { #bindings #tokens } }
+    }
+}
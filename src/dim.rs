@@ -0,0 +1,281 @@
+use proc_macro2::{Delimiter, Group, TokenStream, TokenTree};
+use quote::quote;
+use std::marker::PhantomData;
+
+/// Array-like data, which may be indexed for the purpose of checking dimensions.
+#[diagnostic::on_unimplemented(
+    message = "the data has fewer dimensions than declared",
+    label = "`{Self}` isn't an array, slice or Vec",
+    note = "check the `: DIM` annotation on the element type"
+)]
+pub trait ArrayLike {
+    type Element;
+
+    fn as_slice(&self) -> &[Self::Element];
+}
+
+impl<T, const N: usize> ArrayLike for [T; N] {
+    type Element = T;
+
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T> ArrayLike for [T] {
+    type Element = T;
+
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T> ArrayLike for Vec<T> {
+    type Element = T;
+
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+}
+
+impl<A: ArrayLike + ?Sized> ArrayLike for &A {
+    type Element = A::Element;
+
+    fn as_slice(&self) -> &[A::Element] {
+        (**self).as_slice()
+    }
+}
+
+/// The number of array, slice or Vec layers wrapping a type, for checking declared dimensions.
+///
+/// Implemented for primitive element types and the wrappers around them. Data with other element types
+/// (such as user-defined structs) isn't checked.
+pub trait ArrayDepth {
+    const DEPTH: usize;
+}
+
+macro_rules! leaf_depth {
+    ($($t:ty)*) => {
+        $(
+            impl ArrayDepth for $t {
+                const DEPTH: usize = 0;
+            }
+        )*
+    };
+}
+
+leaf_depth! {
+    bool char f32 f64 i8 i16 i32 i64 i128 isize str String u8 u16 u32 u64 u128 usize
+}
+
+impl<T: ArrayDepth, const N: usize> ArrayDepth for [T; N] {
+    const DEPTH: usize = T::DEPTH + 1;
+}
+
+impl<T: ArrayDepth> ArrayDepth for [T] {
+    const DEPTH: usize = T::DEPTH + 1;
+}
+
+impl<T: ArrayDepth> ArrayDepth for Vec<T> {
+    const DEPTH: usize = T::DEPTH + 1;
+}
+
+impl<T: ArrayDepth + ?Sized> ArrayDepth for &T {
+    const DEPTH: usize = T::DEPTH;
+}
+
+/// Probes the array depth of `T`, if `T` implements [`ArrayDepth`].
+///
+/// The inherent `depth` method applies only when `T: ArrayDepth`; otherwise, method resolution falls back
+/// to [`DepthFallback`]. As this is resolved where the element type is known (in the expansion of a
+/// `write_`... macro), it works for any element type.
+pub struct DepthProbe<T: ?Sized>(PhantomData<T>);
+
+impl<T: ArrayDepth + ?Sized> DepthProbe<T> {
+    pub fn depth(&self) -> Option<usize> {
+        Some(T::DEPTH)
+    }
+}
+
+/// The fallback for [`DepthProbe`], for element types of unknown depth.
+pub trait DepthFallback {
+    fn depth(&self) -> Option<usize> {
+        None
+    }
+}
+
+impl<T: ?Sized> DepthFallback for DepthProbe<T> {}
+
+/// A probe for the array depth of the elements of `arr`.
+pub fn depth_probe<A: ArrayLike + ?Sized>(_arr: &A) -> DepthProbe<A::Element> {
+    DepthProbe(PhantomData)
+}
+
+const PRIMITIVES: &[&str] = &[
+    "bool", "char", "f32", "f64", "i8", "i16", "i32", "i64", "i128", "isize", "str", "String",
+    "u8", "u16", "u32", "u64", "u128", "usize",
+];
+
+/// Whether `t` is certainly not an array type.
+///
+/// We're conservative here, since `t` could be an alias for an array type.
+fn certainly_not_array(t: &syn::Type) -> bool {
+    match t {
+        syn::Type::Tuple(_) => true,
+        syn::Type::Reference(r) => certainly_not_array(&r.elem),
+        syn::Type::Group(g) => certainly_not_array(&g.elem),
+        syn::Type::Paren(p) => certainly_not_array(&p.elem),
+        syn::Type::Path(p) => match p.path.segments.last() {
+            Some(seg) => PRIMITIVES.contains(&seg.ident.to_string().as_str()),
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+/// Panic if the elements at the declared depth of the data are themselves arrays (`extra_dims` deep, if
+/// known), but the declared element type `t` isn't.
+pub fn check_leaf_type(id: &str, dim: usize, t: TokenStream, extra_dims: Option<usize>) {
+    let Some(extra_dims) = extra_dims.filter(|&d| d > 0) else {
+        return;
+    };
+    let Ok(t_parsed) = syn::parse2::<syn::Type>(t.clone()) else {
+        return;
+    };
+    if certainly_not_array(&t_parsed) {
+        panic!(
+            "Couldn't write {}: the data has dimension {}, but the declared dimension is {}. \
+            Try declaring the element type as `{} : {}`.",
+            id,
+            dim + extra_dims,
+            dim,
+            t,
+            dim + extra_dims
+        );
+    }
+}
+
+// Whether the declared element type is `_`, to be inferred from the data. Types passed through
+// `macro_rules!` arrive wrapped in invisible groups, so we compare the type as parsed.
+fn is_infer(t: &TokenStream) -> bool {
+    matches!(syn::parse2::<syn::Type>(t.clone()), Ok(syn::Type::Infer(_)))
+}
+
+/// The element type of the leaf array `arr`: `t` as declared, or inferred from the elements when `t` is `_`.
+pub fn leaf_type<A>(id: &str, t: TokenStream, arr: &A) -> TokenStream
+where
+    A: ArrayLike + ?Sized,
+    A::Element: crate::ToTokenStream,
+{
+    if !is_infer(&t) {
+        return t;
+    }
+    match crate::tokens::elements_type(arr.as_slice()) {
+        Some(inferred) => inferred,
+        None => panic!(
+            "Couldn't write {}: the element type can't be inferred from the data. \
+            Try declaring the element type explicitly.",
+            id
+        ),
+    }
+}
+
+/// Replace the inferred element type `_` in the array type `arr_type` with `leaf_type`.
+pub fn infer_array_type(arr_type: TokenStream, leaf_type: &TokenStream) -> TokenStream {
+    if is_infer(&arr_type) {
+        return leaf_type.clone();
+    }
+    arr_type
+        .into_iter()
+        .map(|tt| match tt {
+            TokenTree::Group(g) => {
+                let stream = infer_array_type(g.stream(), leaf_type);
+                let mut group = Group::new(g.delimiter(), stream);
+                group.set_span(g.span());
+                TokenTree::Group(group)
+            }
+            TokenTree::Ident(i) if i == "_" => {
+                TokenTree::Group(Group::new(Delimiter::None, leaf_type.clone()))
+            }
+            other => other,
+        })
+        .collect()
+}
+
+// The elements of an array, slice or Vec literal (`[..]`, `vec![..]` or a byte string `*b".."`),
+// or `None` if `tokens` isn't such a literal.
+fn array_elements(tokens: TokenStream) -> Option<Vec<TokenStream>> {
+    let tts: Vec<TokenTree> = tokens.into_iter().collect();
+    let group = match tts.as_slice() {
+        [TokenTree::Group(g)] => g,
+        [TokenTree::Ident(i), TokenTree::Punct(p), TokenTree::Group(g)]
+            if i == "vec" && p.as_char() == '!' =>
+        {
+            g
+        }
+        [TokenTree::Punct(p), lit] if p.as_char() == '*' => {
+            let bytes = crate::tokens::byte_string_value(lit)?;
+            return Some(bytes.into_iter().map(|b| quote! { #b }).collect());
+        }
+        _ => return None,
+    };
+    if group.delimiter() != Delimiter::Bracket {
+        return None;
+    }
+    Some(crate::repeat::split_elements(group.stream()))
+}
+
+/// Convert nested arrays, slices or Vecs of dimension `dim` (given as tokens) into nested array
+/// literals, returning them along with their array type.
+///
+/// Unlike the per-dimension macros behind `write_static_array!`, this recurses at runtime, so
+/// supports any dimension.
+pub fn deep_array(
+    id: &str,
+    tokens: TokenStream,
+    dim: usize,
+    leaf_type: &TokenStream,
+) -> (TokenStream, TokenStream) {
+    if dim == 0 {
+        return (tokens, leaf_type.clone());
+    }
+    let Some(elements) = array_elements(tokens) else {
+        panic!(
+            "Couldn't write {}: actual array (or vec) is too shallow",
+            id
+        );
+    };
+    let len = elements.len();
+    if len == 0 && dim > 1 {
+        panic!(
+            "Couldn't write {}: an empty array (or vec) has no elements from which to determine \
+            the lengths of the inner dimensions",
+            id
+        );
+    }
+    let mut element_type = if len == 0 {
+        Some(leaf_type.clone())
+    } else {
+        None
+    };
+    let mut element_toks = Vec::with_capacity(len);
+    for element in elements {
+        let (toks, t) = deep_array(id, element, dim - 1, leaf_type);
+        match &element_type {
+            Some(prev) if prev.to_string() != t.to_string() => panic!(
+                "Couldn't write {}: the arrays (or vecs) of dimension {} differ in length ({} and {})",
+                id,
+                dim - 1,
+                prev,
+                t
+            ),
+            _ => element_type = Some(t),
+        }
+        element_toks.push(toks);
+    }
+    let element_type = element_type.unwrap();
+    (
+        quote! { [#(#element_toks),*] },
+        quote! { [#element_type; #len] },
+    )
+}
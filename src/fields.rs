@@ -0,0 +1,52 @@
+use std::collections::BTreeSet;
+use std::fmt::Display;
+
+// The field names of a struct are kept in a side file, next to the file holding the struct itself.
+fn fields_path(path_str: &str) -> String {
+    format!("{}.fields", path_str)
+}
+
+/// Record the field names of the struct written to `path_str`, for checking by `check_fields`.
+pub fn record_fields<I, S>(path_str: &str, fields: I)
+where
+    I: IntoIterator<Item = S>,
+    S: Display,
+{
+    let mut contents = String::new();
+    for field in fields {
+        contents.push_str(&format!("{}\n", field));
+    }
+    crate::transaction::write_file(fields_path(path_str), contents).unwrap();
+}
+
+/// Panic, naming the field, unless `ids` are exactly the fields recorded for the struct written to
+/// `path_str`.
+///
+/// Structs without recorded fields (such as those written by `write_struct!`) aren't checked.
+pub fn check_fields<I, S>(id_init: &str, id_struct: &str, path_str: &str, ids: I)
+where
+    I: IntoIterator<Item = S>,
+    S: Display,
+{
+    let Ok(contents) = crate::transaction::read_file(fields_path(path_str)) else {
+        return;
+    };
+    let fields: BTreeSet<&str> = contents.lines().collect();
+    let ids: Vec<String> = ids.into_iter().map(|id| id.to_string()).collect();
+    for id in ids.iter() {
+        if !fields.contains(id.as_str()) {
+            panic!(
+                "Couldn't write {}: the struct {} has no field '{}'",
+                id_init, id_struct, id
+            );
+        }
+    }
+    for field in fields {
+        if !ids.iter().any(|id| id == field) {
+            panic!(
+                "Couldn't write {}: the field '{}' of the struct {} isn't initialised",
+                id_init, field, id_struct
+            );
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use std::io;
+use std::path::Path;
+
+/// Read the contents of a file, registering it as an input of the build script.
+///
+/// Emits `cargo:rerun-if-changed` for the file, so that Cargo reruns the build script whenever
+/// it changes. This avoids baking stale data into the binary. Note that once any such file is
+/// registered, Cargo no longer reruns the build script for changes elsewhere in the package.
+///
+/// ## Further notes
+/// * Must be called from a build script (build.rs) only.
+///
+/// ## Example
+/// build.rs
+/// ```no_run
+/// use rustifact::ToTokenStream;
+///
+/// fn main() {
+///     let greeting = rustifact::track_file("data/greeting.txt").unwrap();
+///     rustifact::write_static!(GREETING, &'static str, greeting.trim());
+/// }
+/// ```
+pub fn track_file<P: AsRef<Path>>(path: P) -> io::Result<String> {
+    let path = path.as_ref();
+    println!("cargo:rerun-if-changed={}", path.display());
+    std::fs::read_to_string(path)
+}
+
+/// Load the rows of a CSV file.
+///
+/// Each row is returned as a vector of its fields. Fields may be quoted with `"`, in which case
+/// they may contain commas, line breaks and escaped quotes (`""`). No special treatment is given
+/// to a header row; skip the first row if the file has one.
+///
+/// The file is registered with Cargo via [`track_file`], so edits to it trigger regeneration.
+///
+/// ## Further notes
+/// * Must be called from a build script (build.rs) only.
+///
+/// ## Example
+/// build.rs
+/// ```no_run
+/// use rustifact::ToTokenStream;
+///
+/// fn parse_row(row: Vec<String>) -> (String, u32) {
+///     (row[0].clone(), row[1].parse().unwrap())
+/// }
+///
+/// fn main() {
+///     let rows = rustifact::load_csv("data/cities.csv").unwrap();
+///     let cities: Vec<(String, u32)> = rows.into_iter().skip(1).map(parse_row).collect();
+///     rustifact::write_static_array!(CITIES, (&'static str, u32), &cities);
+/// }
+/// ```
+#[cfg(feature = "csv")]
+pub fn load_csv<P: AsRef<Path>>(path: P) -> io::Result<Vec<Vec<String>>> {
+    let path = path.as_ref();
+    let contents = track_file(path)?;
+    parse_csv(&contents).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: {}", path.display(), e),
+        )
+    })
+}
+
+#[cfg(feature = "csv")]
+fn parse_csv(contents: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut line = 1;
+    let mut chars = contents.chars().peekable();
+    // Whether the current row has any content, so that blank lines are skipped.
+    let mut in_row = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if field.is_empty() => {
+                in_row = true;
+                loop {
+                    match chars.next() {
+                        Some('"') if chars.peek() == Some(&'"') => {
+                            chars.next();
+                            field.push('"');
+                        }
+                        Some('"') => break,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            field.push(c);
+                        }
+                        None => return Err(format!("unterminated quoted field on line {}", line)),
+                    }
+                }
+                match chars.peek() {
+                    None | Some(',') | Some('\n') | Some('\r') => {}
+                    Some(_) => {
+                        return Err(format!("unexpected character after quote on line {}", line))
+                    }
+                }
+            }
+            ',' => {
+                in_row = true;
+                row.push(std::mem::take(&mut field));
+            }
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                if in_row {
+                    row.push(std::mem::take(&mut field));
+                    rows.push(std::mem::take(&mut row));
+                    in_row = false;
+                }
+                line += 1;
+            }
+            c => {
+                in_row = true;
+                field.push(c);
+            }
+        }
+    }
+    if in_row {
+        row.push(field);
+        rows.push(row);
+    }
+    Ok(rows)
+}
@@ -93,6 +93,31 @@
 //! rustifact = "0.10"
 //! ```
 //!
+//! # Deduplication
+//! Arrays written by [`write_static_array`] and [`write_const_array`] whose elements are all identical
+//! (at any depth) are emitted with array-repeat syntax, such as `[0u8; 100000]`.
+//!
+//! With the `dedup` feature enabled, arrays written by [`write_static_array`] and [`write_const_array`] are
+//! checked for repeated elements. Each repeated element is emitted just once, as a `const`, which
+//! can dramatically reduce the size of the generated code for repetitive data.
+//!
+//! # Compression
+//! With the `compress` feature enabled, [`write_compressed_bytes`] bakes large byte blobs in compressed
+//! form, decompressing them on first use at runtime.
+//!
+//! # Loading input files
+//! Files read by the build script should be loaded with [`track_file`], which registers them with Cargo so
+//! that the build script is rerun whenever they change. With the `csv` feature enabled, [`load_csv`] reads
+//! (and likewise registers) a CSV file, returning its rows ready for export. Registering a file stops Cargo
+//! rerunning the build script for edits to the main crate; call [`track_imports`] to keep tracking them.
+//!
+//! # Formatting of generated code
+//! Generated code is pretty-printed for readability. As this is slow for very large outputs, any output
+//! exceeding 1 MiB is written unformatted. The threshold (in bytes) may be adjusted by setting the
+//! `RUSTIFACT_FORMAT_MAX_BYTES` environment variable for the build script. Setting the
+//! `RUSTIFACT_NO_FORMAT` environment variable (to any value) disables pretty-printing altogether, for
+//! tools that consume the generated code directly. Unformatted output is still checked for parse errors.
+//!
 //! # Development status
 //! Please note that _Rustifact_ is in an early development stage.  Overall, it is unlikely to
 //! cause unpleasant surprises, though there may be edge cases that haven't yet been discovered.
@@ -101,16 +126,67 @@
 
 mod tokens;
 
+#[cfg(feature = "compress")]
+mod compress;
+
+#[cfg(feature = "dedup")]
+mod dedup;
+
+mod dim;
+
+mod fields;
+
+mod io;
+
+mod lint;
+
+mod manifest;
+
+mod namespace;
+
+mod packed;
+
 mod phf;
 
+mod repeat;
+
+mod spec;
+
+mod stub;
+
+mod transaction;
+
+mod validate;
+
+mod writer;
+
 #[cfg(feature = "map")]
-pub use crate::phf::{Map, MapBuilder, OrderedMap, OrderedMapBuilder};
+pub use crate::phf::{
+    CaseInsensitiveMap, CaseInsensitiveMapBuilder, Map, MapBuilder, OrderedMap, OrderedMapBuilder,
+};
 
 #[cfg(feature = "set")]
 pub use crate::phf::{OrderedSet, OrderedSetBuilder, Set, SetBuilder};
 
+#[cfg(any(feature = "map", feature = "set"))]
+pub use crate::phf::DuplicateKeyError;
+
+#[cfg(feature = "csv")]
+pub use io::load_csv;
+pub use io::track_file;
+pub use stub::track_imports;
+
+pub use lint::{set_allowed_lints, ALLOWED_LINTS_DEFAULT};
+
+pub use namespace::{clear_namespace, set_namespace};
+
+pub use packed::PackedElement;
 pub use rustifact_derive::ToTokenStream;
-pub use tokens::ToTokenStream;
+pub use spec::FieldSpec;
+pub use tokens::{to_toks_array, DynTokens, ToTokenStream};
+pub use transaction::transaction;
+pub use validate::{defer_validation, validate_all};
+pub use writer::Writer;
 
 /// An implementation detail, exposing parts of external crates used by `rustifact`.
 ///
@@ -132,17 +208,321 @@ pub mod internal {
     pub use syn::parse_str;
     /// A re-export of `Type` from the `syn` crate.
     pub use syn::Type;
+    /// The default size, in bytes, above which generated code is written without pretty-printing.
+    pub const FORMAT_MAX_BYTES_DEFAULT: usize = 1 << 20;
+    /// The size, in bytes, above which generated code is written without pretty-printing.
+    ///
+    /// Set via the `RUSTIFACT_FORMAT_MAX_BYTES` environment variable, defaulting to
+    /// [`FORMAT_MAX_BYTES_DEFAULT`] when the variable is unset or invalid.
+    pub fn format_max_bytes() -> usize {
+        std::env::var("RUSTIFACT_FORMAT_MAX_BYTES")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(FORMAT_MAX_BYTES_DEFAULT)
+    }
+    /// Whether pretty-printing of generated code is disabled, by setting the `RUSTIFACT_NO_FORMAT`
+    /// environment variable.
+    pub fn format_disabled() -> bool {
+        std::env::var_os("RUSTIFACT_NO_FORMAT").is_some()
+    }
+    #[doc(hidden)]
+    pub fn parse_type<S: AsRef<str> + ?Sized>(type_str: &S) -> Type {
+        if let Ok(t) = parse_str::<Type>(type_str.as_ref()) {
+            t
+        } else {
+            panic!("Couldn't parse the type '{}'", type_str.as_ref());
+        }
+    }
+    /// The maximum length, in bytes, of the name of a symbol written by the build script.
+    ///
+    /// Each symbol is written to a file named after it, so longer names risk exceeding the file name
+    /// limits of common file systems.
+    pub const SYMBOL_MAX_LEN: usize = 128;
+    #[doc(hidden)]
+    pub fn path_from_id(id: &str) -> String {
+        check_symbol_len(id);
+        format!(
+            "{}/{}rustifact_{}_{}.rs",
+            generated_dir(),
+            namespace_dir(),
+            std::env::var("CARGO_PKG_NAME").unwrap(),
+            id,
+        )
+    }
+    #[doc(hidden)]
+    pub fn check_symbol_len(id: &str) {
+        if id.len() > SYMBOL_MAX_LEN {
+            panic!(
+                "Couldn't write the symbol '{}': its name is longer than {} bytes",
+                id, SYMBOL_MAX_LEN
+            );
+        }
+    }
+    #[doc(hidden)]
+    pub fn parse_ident<S: std::fmt::Display>(id_str: S) -> proc_macro2::Ident {
+        let id_str = id_str.to_string();
+        if let Ok(id) = parse_str::<proc_macro2::Ident>(&id_str) {
+            id
+        } else {
+            panic!(
+                "Couldn't use '{}' as a name: it isn't a valid Rust identifier",
+                id_str
+            );
+        }
+    }
+    #[doc(hidden)]
+    pub fn visibility(vis: &str) -> TokenStream {
+        match vis {
+            "public" => quote! { pub },
+            "pub_crate" => quote! { pub(crate) },
+            _ => TokenStream::new(),
+        }
+    }
+    #[doc(hidden)]
+    pub fn parse_expr<S: AsRef<str> + ?Sized>(expr_str: &S) -> syn::Expr {
+        if let Ok(e) = parse_str::<syn::Expr>(expr_str.as_ref()) {
+            e
+        } else {
+            panic!("Couldn't parse the expression '{}'", expr_str.as_ref());
+        }
+    }
+    #[doc(hidden)]
+    pub fn parse_source<S: AsRef<str> + ?Sized>(id: &str, source: &S) -> syn::File {
+        match syn::parse_file(source.as_ref()) {
+            Ok(file) => file,
+            Err(e) => panic!("Couldn't parse the source code of {}: {}", id, e),
+        }
+    }
+    #[doc(hidden)]
+    pub fn parse_attributes<I, S>(attrs: I) -> TokenStream
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut tokens = TokenStream::new();
+        for attr_str in attrs {
+            let attr_str = attr_str.as_ref();
+            match syn::parse::Parser::parse_str(syn::Attribute::parse_outer, attr_str) {
+                Ok(attrs) => tokens.extend(quote! { #(#attrs)* }),
+                Err(e) => panic!("Couldn't parse the attribute '{}': {}", attr_str, e),
+            }
+        }
+        tokens
+    }
+    #[doc(hidden)]
+    pub fn parse_derives<I, S>(derives: I) -> TokenStream
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut paths = Vec::new();
+        for derive_str in derives {
+            let derive_str = derive_str.as_ref();
+            match parse_str::<syn::Path>(derive_str) {
+                Ok(path) => paths.push(path),
+                Err(e) => panic!("Couldn't parse the derive '{}': {}", derive_str, e),
+            }
+        }
+        if paths.is_empty() {
+            TokenStream::new()
+        } else {
+            quote! { #[derive(#(#paths),*)] }
+        }
+    }
+    #[doc(hidden)]
+    pub fn parse_repr<S: AsRef<str> + ?Sized>(repr: &S) -> TokenStream {
+        const REPRS: [&str; 17] = [
+            "C",
+            "Rust",
+            "transparent",
+            "packed",
+            "align",
+            "u8",
+            "u16",
+            "u32",
+            "u64",
+            "u128",
+            "usize",
+            "i8",
+            "i16",
+            "i32",
+            "i64",
+            "i128",
+            "isize",
+        ];
+        let repr = repr.as_ref();
+        let parser = syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated;
+        let metas = match syn::parse::Parser::parse_str(parser, repr) {
+            Ok(metas) => metas,
+            Err(e) => panic!("Couldn't parse the repr '{}': {}", repr, e),
+        };
+        for meta in metas.iter() {
+            let known = meta
+                .path()
+                .get_ident()
+                .is_some_and(|id| REPRS.contains(&id.to_string().as_str()));
+            if !known {
+                panic!("Couldn't parse the repr '{}': unknown representation", repr);
+            }
+        }
+        if metas.is_empty() {
+            TokenStream::new()
+        } else {
+            quote! { #[repr(#metas)] }
+        }
+    }
+    #[doc(hidden)]
+    pub fn parse_methods<I, S, B>(sigs_bodies: I) -> TokenStream
+    where
+        I: IntoIterator,
+        I::Item: std::borrow::Borrow<(S, B)>,
+        S: AsRef<str>,
+        B: AsRef<str>,
+    {
+        let mut tokens = TokenStream::new();
+        for sig_body in sigs_bodies {
+            let (sig, body) = std::borrow::Borrow::borrow(&sig_body);
+            let method_str = format!("{} {{ {} }}", sig.as_ref(), body.as_ref());
+            match parse_str::<syn::ImplItemFn>(&method_str) {
+                Ok(method) => tokens.extend(quote! { #method }),
+                Err(e) => panic!("Couldn't parse the method '{}': {}", sig.as_ref(), e),
+            }
+        }
+        tokens
+    }
+    #[doc(hidden)]
+    pub fn pack<T: crate::PackedElement>(data: &[T]) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(data.len() * T::SIZE);
+        for element in data {
+            element.write_le_bytes(&mut bytes);
+        }
+        // The bytes are reinterpreted as elements on the target, so must be in its byte order.
+        if std::env::var("CARGO_CFG_TARGET_ENDIAN").as_deref() == Ok("big") {
+            for element in bytes.chunks_mut(T::SIZE) {
+                element.reverse();
+            }
+        }
+        bytes
+    }
+    #[doc(hidden)]
+    #[cfg(feature = "compress")]
+    pub use crate::compress::{deflate, inflate};
+    #[doc(hidden)]
+    pub use crate::dim::{check_leaf_type, depth_probe, DepthFallback};
+    #[doc(hidden)]
+    pub use crate::dim::{deep_array, infer_array_type, leaf_type, ArrayLike};
+    #[doc(hidden)]
+    pub use crate::fields::{check_fields, record_fields};
+    #[doc(hidden)]
+    pub use crate::lint::allow_lints;
+    #[doc(hidden)]
+    pub use crate::manifest::record_symbol;
+    #[doc(hidden)]
+    pub use crate::namespace::{generated_dir, namespace_dir};
+    #[doc(hidden)]
+    pub use crate::repeat::compress_repeats;
+    #[doc(hidden)]
+    pub use crate::stub::write_stubs;
+    #[doc(hidden)]
+    pub use crate::tokens::{entry_types, sort_by_tokens};
+    #[doc(hidden)]
+    pub use crate::transaction::{read_file, write_file, write_file_with};
+    #[doc(hidden)]
+    pub use crate::validate::{check_unwritten, record_written, validation_deferred};
+    #[doc(hidden)]
+    pub use crate::writer::write_tokens;
+    #[doc(hidden)]
+    pub fn check_const_safe<T: crate::ToTokenStream + ?Sized>(id: &str, data: &T) {
+        if !data.is_const_safe() {
+            panic!(
+                "Couldn't write {} as a const: its data can't be constructed in a const context (for example, \
+                it's heap-allocated). Try write_fn! instead.",
+                id
+            );
+        }
+    }
+    #[doc(hidden)]
+    pub fn dedup_array(tokens: TokenStream, dim: usize, leaf_type: TokenStream) -> TokenStream {
+        #[cfg(feature = "dedup")]
+        {
+            crate::dedup::dedup_leaves(tokens, dim, leaf_type)
+        }
+        #[cfg(not(feature = "dedup"))]
+        {
+            let _ = (dim, leaf_type);
+            tokens
+        }
+    }
+    #[doc(hidden)]
+    pub fn write_slice_streaming<I>(path_str: &str, id: &str, t: &TokenStream, data: I)
+    where
+        I: IntoIterator,
+        I::Item: crate::ToTokenStream,
+    {
+        use crate::ToTokenStream;
+        use std::io::Write;
+        write_stubs();
+        crate::validate::check_unwritten(id, path_str);
+        write_file_with(path_str, |writer| {
+            writeln!(writer, "static {}: &'static [{}] = &[", id, t)?;
+            for element in data {
+                writeln!(writer, "    {},", element.to_tok_stream())?;
+            }
+            writeln!(writer, "];")
+        })
+        .unwrap();
+        // The slice is written as a single private static, so is visible to the parent of a hidden module
+        // once prefixed with pub(super).
+        let contents = crate::transaction::read_file(path_str).unwrap();
+        write_file(
+            crate::writer::alias_path(path_str),
+            format!("pub(super) {}", contents),
+        )
+        .unwrap();
+        crate::manifest::record_symbol(path_str);
+        crate::validate::record_written(id, path_str);
+    }
+    #[doc(hidden)]
+    pub fn byte_string<B: AsRef<[u8]> + ?Sized>(bytes: &B) -> TokenStream {
+        let lit = proc_macro2::Literal::byte_string(bytes.as_ref());
+        quote! { #lit }
+    }
+    #[doc(hidden)]
+    pub fn make_public(asset: &str) -> String {
+        match syn::parse_file(asset) {
+            Ok(mut file) => {
+                crate::lint::make_public(&mut file);
+                quote::ToTokens::to_token_stream(&file).to_string()
+            }
+            Err(_) => format!("pub {}", asset),
+        }
+    }
     #[doc(hidden)]
-    pub fn allow_export_error(id: &str) -> String {
+    pub fn write_mod_error(id: &str, id_mod: &str) -> String {
         format!(
             concat!(
-                "Couldn't find symbol {} to setup export.",
-                "Ensure you call write_static (or another write_... function)",
-                "for {} before allow_export",
+                "Couldn't find symbol {} to place in module {}. ",
+                "Ensure you call write_static (or another write_... function) ",
+                "for {} before write_mod",
             ),
-            id, id
+            id, id_mod, id
         )
     }
+    #[doc(hidden)]
+    pub fn allow_export_error(id: &str, err: &std::io::Error) -> String {
+        if err.kind() == std::io::ErrorKind::NotFound {
+            format!(
+                concat!(
+                    "Couldn't find symbol {} to setup export, as it hasn't been written yet. ",
+                    "Ensure you call write_static (or another write_... function) ",
+                    "for {} before allow_export!({}) in the build script",
+                ),
+                id, id, id
+            )
+        } else {
+            format!("Couldn't read symbol {} to setup export: {}", id, err)
+        }
+    }
 }
 
 #[doc(hidden)]
@@ -208,59 +588,173 @@ macro_rules! __get_tokens_vector_fn_impl {
 #[doc(hidden)]
 #[macro_export]
 macro_rules! __path_from_id {
-    ($id_name:ident, private) => {{
-        format!(
-            "{}/rustifact_{}_{}.rs",
-            std::env::var("OUT_DIR").unwrap(),
-            std::env::var("CARGO_PKG_NAME").unwrap(),
-            stringify!($id_name),
-        )
-    }};
-    ($id_name:ident, public) => {{
-        format!(
-            "{}/rustifact__pub__{}_{}.rs",
-            std::env::var("OUT_DIR").unwrap(),
-            std::env::var("CARGO_PKG_NAME").unwrap(),
-            stringify!($id_name),
-        )
-    }};
-    ($id_name:expr, private) => {{
-        format!(
-            "{}/rustifact_{}_{}.rs",
-            std::env::var("OUT_DIR").unwrap(),
-            std::env::var("CARGO_PKG_NAME").unwrap(),
-            $id_name,
-        )
-    }};
+    ($id_name:ident, $vis:ident) => {
+        rustifact::__path_from_id!(stringify!($id_name), $vis)
+    };
+    ($id_name:expr, private) => {
+        rustifact::internal::path_from_id(&$id_name.to_string())
+    };
     ($id_name:expr, public) => {{
+        let id_name = $id_name.to_string();
+        rustifact::internal::check_symbol_len(&id_name);
         format!(
-            "{}/rustifact__pub__{}_{}.rs",
-            std::env::var("OUT_DIR").unwrap(),
+            "{}/{}rustifact__pub__{}_{}.rs",
+            rustifact::internal::generated_dir(),
+            rustifact::internal::namespace_dir(),
             std::env::var("CARGO_PKG_NAME").unwrap(),
-            $id_name,
+            id_name,
         )
     }};
 }
 
 /// Import the given symbols (generated by the build script) into scope.
 ///
+/// Importing a symbol that the build script doesn't write produces a compile error naming the symbol.
+///
+/// A symbol may also be imported under an alias, with `use_symbols!(get_c as config_rows)`. The symbol is
+/// then included into a hidden module named after the alias, so its original name stays free for other
+/// items, and aliased imports work inside function bodies too. As the alias names both the module and the
+/// symbol, only constants, statics and functions may be aliased, not types.
+///
+/// Symbols written in a namespace (see [`set_namespace`]) are imported with `use_symbols!(in ns: FOO, BAR)`.
+///
 /// # Limitations
 /// Any types referenced by the imported symbols must be manually brought into scope.
 /// This may not be necessary in future versions of *Rustifact*.
 /// See the relevant [tracking issue](https://github.com/mbaulch/rustifact/issues/4).
 #[macro_export]
 macro_rules! use_symbols {
-    ($($id_name:ident),*) => {
+    (in $ns:ident : $($id_name:ident $(as $alias:ident)?),*) => {
         $(
+            $crate::__use_symbol!([$ns] $id_name $(as $alias)?);
+        )*
+    };
+    ($($id_name:ident $(as $alias:ident)?),*) => {
+        $(
+            $crate::__use_symbol!([] $id_name $(as $alias)?);
+        )*
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __use_symbol {
+    ([$($ns:ident)?] $id_name:ident) => {
+        include!(concat!(
+            env!("OUT_DIR"),
+            "/rustifact/",
+            $(stringify!($ns), "/",)?
+            "rustifact_",
+            env!("CARGO_PKG_NAME"),
+            "_",
+            stringify!($id_name),
+            ".rs"
+        ));
+    };
+    ([$($ns:ident)?] $id_name:ident as $alias:ident) => {
+        #[doc(hidden)]
+        #[allow(non_snake_case)]
+        mod $alias {
+            #[allow(unused_imports)]
+            use super::*;
             include!(concat!(
                 env!("OUT_DIR"),
-                "/rustifact_",
+                "/rustifact/",
+                $(stringify!($ns), "/",)?
+                "rustifact_",
                 env!("CARGO_PKG_NAME"),
                 "_",
                 stringify!($id_name),
-                ".rs"
+                ".rs.alias"
             ));
-        )*
+        }
+        #[allow(unused_imports)]
+        use $alias::$id_name as $alias;
+    };
+}
+
+/// The path of the file holding the given symbol (generated by the build script), as a `&'static str`.
+///
+/// Intended for debugging, for example printing the path, or embedding the generated code with
+/// `include_str!(rustifact::generated_path!(FOO))`. The layout of files in `OUT_DIR` is an implementation
+/// detail, and may change between versions of *Rustifact*.
+///
+/// Symbols written in a namespace (see [`set_namespace`]) are located with `generated_path!(in ns: FOO)`.
+///
+/// ## Example
+/// src/main.rs
+/// ```no_run
+/// rustifact::use_symbols!(FOO);
+///
+/// fn main() {
+///     println!("FOO is generated in {}", rustifact::generated_path!(FOO));
+/// }
+/// ```
+#[macro_export]
+macro_rules! generated_path {
+    (in $ns:ident : $id_name:ident) => {
+        concat!(
+            env!("OUT_DIR"),
+            "/rustifact/",
+            stringify!($ns),
+            "/rustifact_",
+            env!("CARGO_PKG_NAME"),
+            "_",
+            stringify!($id_name),
+            ".rs"
+        )
+    };
+    ($id_name:ident) => {
+        concat!(
+            env!("OUT_DIR"),
+            "/rustifact/rustifact_",
+            env!("CARGO_PKG_NAME"),
+            "_",
+            stringify!($id_name),
+            ".rs"
+        )
+    };
+}
+
+/// Import every symbol written by the build script into scope.
+///
+/// This suits build scripts which write a large, or variable, set of symbols. Symbols written in a
+/// namespace (see [`set_namespace`]), struct initialisation expressions, and impl blocks aren't imported.
+/// Only the symbols written by the most recent run of the build script are imported, so a symbol that's no
+/// longer written won't linger.
+///
+/// # Example
+/// build.rs
+/// ```no_run
+/// use rustifact::ToTokenStream;
+///
+/// fn main() {
+///     rustifact::write_const!(RED, u32, &0xff0000u32);
+///     rustifact::write_const!(GREEN, u32, &0x00ff00u32);
+///     rustifact::write_fn!(get_palette, Vec<u32>, &vec![0xff0000u32, 0x00ff00]);
+/// }
+/// ```
+///
+/// src/main.rs
+/// ```no_run
+/// rustifact::use_all_symbols!();
+/// // The above line is equivalent to:
+/// // rustifact::use_symbols!(RED, GREEN, get_palette);
+/// ```
+///
+/// # Limitations
+/// Any types referenced by the imported symbols must be manually brought into scope.
+/// This may not be necessary in future versions of *Rustifact*.
+/// See the relevant [tracking issue](https://github.com/mbaulch/rustifact/issues/4).
+#[macro_export]
+macro_rules! use_all_symbols {
+    () => {
+        include!(concat!(
+            env!("OUT_DIR"),
+            "/rustifact/rustifact_",
+            env!("CARGO_PKG_NAME"),
+            ".manifest.rs"
+        ));
     };
 }
 
@@ -278,10 +772,12 @@ macro_rules! use_symbols {
 
 #[macro_export]
 macro_rules! export_symbols {
-    ($($id_name:ident),*) => {
+    (in $ns:ident : $($id_name:ident),*) => {
         $(
             include!(concat!(
                 env!("OUT_DIR"),
+                "/rustifact/",
+                stringify!($ns),
                 "/rustifact__pub__",
                 env!("CARGO_PKG_NAME"),
                 "_",
@@ -290,6 +786,18 @@ macro_rules! export_symbols {
             ));
         )*
     };
+    ($($id_name:ident),*) => {
+        $(
+            include!(concat!(
+                env!("OUT_DIR"),
+                "/rustifact/rustifact__pub__",
+                env!("CARGO_PKG_NAME"),
+                "_",
+                stringify!($id_name),
+                ".rs"
+            ));
+        )*
+    };
 }
 
 #[doc = "Setup the symbol for export from the main crate.
@@ -322,57 +830,203 @@ rustifact::export_symbols!(FOO);
 macro_rules! allow_export {
     ($id_name:ident) => {{
         let private_path_str = rustifact::__path_from_id!($id_name, private);
-        let asset_str;
-        if let Ok(s) = std::fs::read_to_string(private_path_str) {
-            asset_str = s;
-        } else {
-            panic!(
+        let asset_str = match rustifact::internal::read_file(private_path_str) {
+            Ok(s) => s,
+            Err(e) => panic!(
                 "{}",
-                rustifact::internal::allow_export_error(stringify!($id_name))
-            );
-        }
-        rustifact::__write_tokens_with_internal!($id_name, public, format!("pub {}", asset_str));
+                rustifact::internal::allow_export_error(stringify!($id_name), &e)
+            ),
+        };
+        rustifact::__write_tokens_with_internal!(
+            $id_name,
+            public,
+            rustifact::internal::make_public(&asset_str)
+        );
     }};
 }
 
-/// Import the given struct initialisation expression (generated by the build script) into scope.
-///
-/// # Limitations
-/// Any types referenced by the imported symbols must be manually brought into scope.
-/// This may not be necessary in future versions of *Rustifact*.
-/// See the relevant [tracking issue](https://github.com/mbaulch/rustifact/issues/4).
-#[macro_export]
-macro_rules! init_symbols {
-    ($id_struct:ident, $id_vals:ident) => {
-        include!(concat!(
-            env!("OUT_DIR"),
-            "/rustifact_",
-            env!("CARGO_PKG_NAME"),
-            "_",
-            stringify!($id_struct),
-            "_",
-            stringify!($id_vals),
-            ".rs"
-        ));
-    };
-}
+#[doc = "Wrap previously written symbols in a module.
 
-#[doc(hidden)]
-#[macro_export]
-macro_rules! __array_type_impl {
-    (0, $t:ty, $data:expr) => {{
-        let len = $data.len();
-        rustifact::internal::quote! { [$t; #len] }
-    }};
-    ($dim:tt, $t:ty, $data:expr) => {{
-        let data_next = $data[0];
-        let inner = rustifact::__array_type!($dim, $t, data_next);
-        let len = $data.len();
-        rustifact::internal::quote! { [#inner; #len] }
-    }};
-}
+Combines the given symbols into a single module, available for import into the main crate via `use_symbols`.
+Before calling `write_mod!` each symbol must be output from the build script with one of the usual `write_`...
+macros.
 
-#[doc(hidden)]
+## Parameters
+* `public` or `private`: whether to make the module publicly visible after import with `use_symbols`.
+* `$id_mod`: the name of the module, and the identifier by which it is referred when importing with `use_symbols`.
+* `$id_name`, ...: the symbols to place in the module.
+
+## Notes
+* The module brings the items of its parent module into scope, so types referenced by the symbols
+need only be in scope where `use_symbols` is called.
+* Only symbols declared `pub` (for example, with `write_statics!(public, ...)`) are accessible from
+outside the module.
+
+## Example
+build.rs
+ ```no_run
+use rustifact::ToTokenStream;
+
+fn main() {
+    let colors = vec![(\"RED\", (255u8, 0u8, 0u8)), (\"GREEN\", (0u8, 255u8, 0u8))];
+    rustifact::write_statics!(public, COLORS, (u8, u8, u8), &colors);
+    rustifact::write_mod!(private, colors, COLORS);
+}
+```
+
+src/main.rs
+```no_run
+rustifact::use_symbols!(colors);
+// The above line is equivalent to the declaration:
+// mod colors {
+//     use super::*;
+//     pub static RED: (u8, u8, u8) = (255, 0, 0);
+//     pub static GREEN: (u8, u8, u8) = (0, 255, 0);
+// }
+
+fn main() {
+    assert!(colors::RED == (255, 0, 0));
+}
+```"]
+#[macro_export]
+macro_rules! write_mod {
+    (public, $id_mod:ident, $($id_name:ident),+) => {
+        rustifact::__write_internal_mod!($id_mod, true, $($id_name),+);
+    };
+    (private, $id_mod:ident, $($id_name:ident),+) => {
+        rustifact::__write_internal_mod!($id_mod, false, $($id_name),+);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __write_internal_mod {
+    ($id_mod:ident, $public:literal, $($id_name:ident),+) => {{
+        let mut toks = rustifact::internal::TokenStream::new();
+        $(
+            let private_path_str = rustifact::__path_from_id!($id_name, private);
+            let asset_str;
+            if let Ok(s) = rustifact::internal::read_file(private_path_str) {
+                asset_str = s;
+            } else {
+                panic!(
+                    "{}",
+                    rustifact::internal::write_mod_error(stringify!($id_name), stringify!($id_mod))
+                );
+            }
+            match asset_str.parse::<rustifact::internal::TokenStream>() {
+                Ok(asset_toks) => toks.extend(asset_toks),
+                Err(e) => panic!("Couldn't parse symbol {}: '{}'", stringify!($id_name), e),
+            }
+        )+
+        let toks_mod = if $public {
+            rustifact::internal::quote! {
+                pub mod $id_mod {
+                    #[allow(unused_imports)]
+                    use super::*;
+                    #toks
+                }
+            }
+        } else {
+            rustifact::internal::quote! {
+                mod $id_mod {
+                    #[allow(unused_imports)]
+                    use super::*;
+                    #toks
+                }
+            }
+        };
+        rustifact::__write_tokens_with_internal!($id_mod, private, toks_mod);
+    }};
+}
+
+/// Import the given struct initialisation expression (generated by the build script) into scope.
+///
+/// Expands to an expression, so may give the value of a `const` as well as a `static`, provided that the
+/// field values can be constructed in a const context (see `write_struct_uniform_init!`).
+///
+/// # Limitations
+/// Any types referenced by the imported symbols must be manually brought into scope.
+/// This may not be necessary in future versions of *Rustifact*.
+/// See the relevant [tracking issue](https://github.com/mbaulch/rustifact/issues/4).
+#[macro_export]
+macro_rules! init_symbols {
+    (in $ns:ident : $id_struct:ident, $id_vals:ident) => {
+        include!(concat!(
+            env!("OUT_DIR"),
+            "/rustifact/",
+            stringify!($ns),
+            "/rustifact_",
+            env!("CARGO_PKG_NAME"),
+            "_",
+            stringify!($id_struct),
+            "_",
+            stringify!($id_vals),
+            ".rs"
+        ));
+    };
+    ($id_struct:ident, $id_vals:ident) => {
+        include!(concat!(
+            env!("OUT_DIR"),
+            "/rustifact/rustifact_",
+            env!("CARGO_PKG_NAME"),
+            "_",
+            stringify!($id_struct),
+            "_",
+            stringify!($id_vals),
+            ".rs"
+        ));
+    };
+}
+
+/// Import the given impl block (generated by the build script) into scope.
+///
+/// # Limitations
+/// Any types referenced by the imported symbols must be manually brought into scope.
+/// This may not be necessary in future versions of *Rustifact*.
+/// See the relevant [tracking issue](https://github.com/mbaulch/rustifact/issues/4).
+#[macro_export]
+macro_rules! impl_symbols {
+    (in $ns:ident : $id_type:ident) => {
+        include!(concat!(
+            env!("OUT_DIR"),
+            "/rustifact/",
+            stringify!($ns),
+            "/rustifact_",
+            env!("CARGO_PKG_NAME"),
+            "_",
+            stringify!($id_type),
+            "_impl.rs"
+        ));
+    };
+    ($id_type:ident) => {
+        include!(concat!(
+            env!("OUT_DIR"),
+            "/rustifact/rustifact_",
+            env!("CARGO_PKG_NAME"),
+            "_",
+            stringify!($id_type),
+            "_impl.rs"
+        ));
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __array_type_impl {
+    (0, $t:ty, $data:expr) => {{
+        let len = $data.len();
+        rustifact::internal::quote! { [$t; #len] }
+    }};
+    ($dim:tt, $t:ty, $data:expr) => {{
+        let data_next = $data[0];
+        let inner = rustifact::__array_type!($dim, $t, data_next);
+        let len = $data.len();
+        rustifact::internal::quote! { [#inner; #len] }
+    }};
+}
+
+#[doc(hidden)]
 #[macro_export]
 macro_rules! __vector_type_impl {
     (0, $t:ty, $_:expr) => {
@@ -389,24 +1043,12 @@ macro_rules! __vector_type_impl {
 macro_rules! __write_tokens_with_internal {
     ($id_name:ident, $visibility:ident, $tokens:expr) => {
         let path_str = rustifact::__path_from_id!($id_name, $visibility);
-        let path = std::path::Path::new(&path_str);
-        match rustifact::internal::parse_file(&$tokens.to_string()) {
-            Ok(syntax_tree) => {
-                let formatted = rustifact::internal::unparse(&syntax_tree);
-                std::fs::write(&path, formatted).unwrap();
-            }
-            Err(e) => {
-                std::fs::write(&path, &$tokens.to_string()).unwrap();
-                panic!(
-                    "Failed to pretty-print {} due to parse error: '{}'
-This _probably_ indicates in issue with a ToTokenStream implementation. Unformatted output has
-been written to {}",
-                    stringify!(id_name),
-                    e,
-                    path.display()
-                );
-            }
-        }
+        rustifact::internal::write_tokens(
+            stringify!($id_name),
+            &path_str,
+            &$tokens,
+            stringify!($visibility) == "private",
+        );
     };
 }
 
@@ -414,19 +1056,33 @@ been written to {}",
 #[macro_export]
 macro_rules! __write_tokens_with_internal_raw {
     ($id_name:expr, $tokens:expr) => {
+        rustifact::internal::write_stubs();
         let path_str = rustifact::__path_from_id!($id_name, private);
+        rustifact::internal::check_unwritten(&$id_name.to_string(), &path_str);
         let path = std::path::Path::new(&path_str);
-        std::fs::write(&path, &$tokens.to_string()).unwrap();
+        rustifact::internal::write_file(&path, $tokens.to_string()).unwrap();
+        rustifact::internal::record_written(&$id_name.to_string(), &path_str);
     };
 }
 
 #[doc(hidden)]
 #[macro_export]
 macro_rules! __write_with_internal {
-    ($const_static:ident, $id_name:ident, $arr_type:expr, $tokens_data:expr) => {{
+    ($const_static:ident, $id_name:ident, $arr_type:expr, $tokens_data:expr) => {
+        rustifact::__write_with_internal!(
+            $const_static,
+            $id_name,
+            $arr_type,
+            $tokens_data,
+            rustifact::internal::TokenStream::new()
+        )
+    };
+    ($const_static:ident, $id_name:ident, $arr_type:expr, $tokens_data:expr, $attrs:expr) => {{
         let arr_type = $arr_type;
         let tokens_data = $tokens_data;
+        let attrs = $attrs;
         let tokens = rustifact::internal::quote! {
+            #attrs
             $const_static $id_name: #arr_type = #tokens_data;
         };
         rustifact::__write_tokens_with_internal!($id_name, private, tokens);
@@ -436,6 +1092,14 @@ macro_rules! __write_with_internal {
 #[doc(hidden)]
 #[macro_export]
 macro_rules! __write_fn_with_internal {
+    (const, $id_name:ident, $t:expr, $tokens_data:expr) => {{
+        let t = $t;
+        let tokens_data = $tokens_data;
+        let tokens = rustifact::internal::quote! {
+            const fn $id_name() -> #t { #tokens_data }
+        };
+        rustifact::__write_tokens_with_internal!($id_name, private, tokens);
+    }};
     ($_:ident, $id_name:ident, $vec_type:expr, $tokens_data:expr) => {{
         let vec_type = $vec_type;
         let tokens_data = $tokens_data;
@@ -449,14 +1113,32 @@ macro_rules! __write_fn_with_internal {
 #[doc(hidden)]
 #[macro_export]
 macro_rules! __assert_dim_impl {
-    (0, $arr:expr) => {};
-    ($dim:tt, $arr:expr) => {
-        if $arr.len() == 0 {
-            panic!("Actual array (or vec) is too shallow");
+    (0, $arr:expr, $id_name:ident, $t:ty, $declared_dim:tt) => {{
+        #[allow(unused_imports)]
+        use rustifact::internal::DepthFallback as _;
+        rustifact::internal::check_leaf_type(
+            stringify!($id_name),
+            $declared_dim,
+            rustifact::internal::quote! { $t },
+            rustifact::internal::depth_probe(&$arr).depth(),
+        );
+        rustifact::internal::leaf_type(
+            stringify!($id_name),
+            rustifact::internal::quote! { $t },
+            &$arr,
+        )
+    }};
+    ($dim:tt, $arr:expr, $id_name:ident, $t:ty, $declared_dim:tt) => {{
+        let arr_ref = &$arr;
+        let arr_slice = rustifact::internal::ArrayLike::as_slice(arr_ref);
+        if arr_slice.is_empty() {
+            panic!(
+                "Couldn't write {}: actual array (or vec) is too shallow",
+                stringify!($id_name)
+            );
         }
-        let arr_first = $arr[0];
-        rustifact::__assert_dim!($dim, arr_first);
-    };
+        rustifact::__assert_dim!($dim, (arr_slice[0]), $id_name, $t, $declared_dim)
+    }};
 }
 
 #[doc(hidden)]
@@ -464,13 +1146,42 @@ macro_rules! __assert_dim_impl {
 macro_rules! __write_with_impl {
     (
         $dim:tt, $const_static:ident, $id_name:ident, $t:ty, $data:expr,
-        $get_tokens:ident, $get_type:ident, $write_internal:ident
+        $get_tokens:ident, $get_type:ident, $write_internal:ident $(, $with_len:ident)?
     ) => {{
-        rustifact::__assert_dim!($dim, $data);
-        let tokens_data = rustifact::$get_tokens!($dim, $data);
-        let arr_type = rustifact::$get_type!($dim, $t, $data);
-        rustifact::$write_internal!($const_static, $id_name, arr_type, tokens_data);
+        let leaf_type = rustifact::__assert_dim!($dim, $data, $id_name, $t, $dim);
+        let mut tokens_data = rustifact::$get_tokens!($dim, $data);
+        if stringify!($const_static) != "dummy" {
+            tokens_data =
+                rustifact::internal::compress_repeats(tokens_data, $dim, leaf_type.clone());
+            tokens_data = rustifact::internal::dedup_array(tokens_data, $dim, leaf_type.clone());
+        }
+        let arr_type = rustifact::internal::infer_array_type(
+            rustifact::$get_type!($dim, $t, $data),
+            &leaf_type,
+        );
+        rustifact::__write_with_impl!(
+            @finish $const_static, $id_name, $data, arr_type, tokens_data,
+            $write_internal $(, $with_len)?
+        );
     }};
+    (
+        @finish $const_static:ident, $id_name:ident, $data:expr, $arr_type:ident,
+        $tokens_data:ident, $write_internal:ident
+    ) => {
+        rustifact::$write_internal!($const_static, $id_name, $arr_type, $tokens_data);
+    };
+    (
+        @finish $const_static:ident, $id_name:ident, $data:expr, $arr_type:ident,
+        $tokens_data:ident, $write_internal:ident, with_len
+    ) => {
+        let len: usize = rustifact::internal::ArrayLike::as_slice(&$data).len();
+        let id_len = rustifact::internal::parse_ident(format!("{}_LEN", stringify!($id_name)));
+        let tokens = rustifact::internal::quote! {
+            $const_static $id_name: #$arr_type = #$tokens_data;
+            const #id_len: usize = #len;
+        };
+        rustifact::__write_tokens_with_internal!($id_name, private, tokens);
+    };
 }
 
 use_symbols!(
@@ -494,6 +1205,8 @@ Makes the variable available for import into the main crate via `use_symbols`.
 * `$id`: the name of the static variable. This must be used when importing with `use_symbols`.
 * `$t`: the type of the static variable.
 * `$data`: the data to assign to the static variable. Must be representable on the stack.
+* `$attrs` (optional): a list of attributes, such as `#[doc = \"...\"]` or `#[allow(dead_code)]`, to place on
+the declaration. Has type `&[S]` where S is String or &str.
 
 ## Example
 build.rs
@@ -535,26 +1248,975 @@ fn main() {
 }
 ```"]
 #[macro_export]
-macro_rules! write_static {
+macro_rules! write_static {
+    ($id:ident, $t:ty, $data:expr) => {
+        let data = $data;
+        rustifact::__write_with_internal!(
+            static,
+            $id,
+            rustifact::internal::quote! { $t },
+            data.to_tok_stream()
+        );
+    };
+    ($id:ident, $t:ty, $data:expr, $attrs:expr) => {
+        let data = $data;
+        rustifact::__write_with_internal!(
+            static,
+            $id,
+            rustifact::internal::quote! { $t },
+            data.to_tok_stream(),
+            rustifact::internal::parse_attributes($attrs)
+        );
+    };
+}
+
+#[doc = "Write a static variable, whose value is the given Rust expression.
+
+Makes the static variable available for import into the main crate via `use_symbols`. Unlike `write_static!`,
+the value isn't built from data in the build script, but is parsed from source code and emitted verbatim. This
+allows the value to refer to other symbols, such as constants generated by the build script.
+
+## Parameters
+* `$id`: the name of the static variable. This must be used when importing with `use_symbols`.
+* `$t`: the type of the static variable.
+* `$expr`: the source code of an expression of type `$t`, having type String or &str. Panics if it can't be
+parsed as an expression. Any symbols it refers to must be in scope where `use_symbols` is invoked.
+
+## Example
+build.rs
+ ```no_run
+use rustifact::ToTokenStream;
+
+fn main() {
+    rustifact::write_const!(WIDTH, u32, &640u32);
+    rustifact::write_static_expr!(DOUBLE_WIDTH, u32, \"WIDTH * 2\");
+}
+```
+
+src/main.rs
+```no_run
+rustifact::use_symbols!(WIDTH, DOUBLE_WIDTH);
+// The above line is equivalent to the declarations:
+// const WIDTH: u32 = 640u32;
+// static DOUBLE_WIDTH: u32 = WIDTH * 2;
+```"]
+#[macro_export]
+macro_rules! write_static_expr {
+    ($id:ident, $t:ty, $expr:expr) => {
+        let expr = rustifact::internal::parse_expr($expr);
+        rustifact::__write_with_internal!(
+            static,
+            $id,
+            rustifact::internal::quote! { $t },
+            rustifact::internal::quote! { #expr }
+        );
+    };
+}
+
+#[doc = "Write a static `Map` from the entries of a standard library map.
+
+Builds a [`MapBuilder`] from `$data` and writes it via `write_static!`, saving the boilerplate of adding
+each entry by hand.
+
+*This API requires the following crate feature to be activated: `map`*
+
+## Parameters
+* `$id`: the name of the static variable. This must be used when importing with `use_symbols`.
+* `$t`: the type of the static variable, such as `Map<&'static str, u32>`.
+* `$data`: a reference to the map, such as a `&HashMap<K, V>` or `&BTreeMap<K, V>`. In general, anything
+iterable over `(&K, &V)` where `K` and `V` are `Clone`. Panics if any key occurs more than once. Entries
+are sorted first, so the output is identical between builds, whatever the iteration order of `$data`.
+
+## Example
+build.rs
+```no_run
+use rustifact::ToTokenStream;
+use std::collections::BTreeMap;
+
+fn main() {
+    let mut ports = BTreeMap::new();
+    ports.insert(\"http\", 80u16);
+    ports.insert(\"https\", 443u16);
+    rustifact::write_phf_map!(PORTS, Map<&'static str, u16>, &ports);
+}
+```
+
+src/main.rs
+```no_run
+use rustifact::Map;
+
+rustifact::use_symbols!(PORTS);
+
+fn main() {
+    assert!(PORTS.get(\"https\") == Some(&443));
+}
+```"]
+#[cfg(feature = "map")]
+#[macro_export]
+macro_rules! write_phf_map {
+    ($id:ident, $t:ty, $data:expr) => {
+        let mut entries: Vec<(_, _)> = std::iter::IntoIterator::into_iter($data)
+            .map(|(k, v)| (std::clone::Clone::clone(k), std::clone::Clone::clone(v)))
+            .collect();
+        rustifact::internal::sort_by_tokens(&mut entries, |(k, _)| k.to_tok_stream());
+        let builder: rustifact::MapBuilder<_, _> = entries.into_iter().collect();
+        rustifact::write_static!($id, $t, &builder);
+    };
+}
+
+#[doc = "Write a static `Map` from the entries of a standard library map, inferring its type.
+
+As with `write_phf_map!`, but the key and value types are determined from the entries, so the static
+variable has type `Map<K, V>` for the emitted key and value types (for example, `Map<&'static str, u16>` for
+a `HashMap<String, u16>`). [`write_std_map_fn`] accepts the same input, exporting a standard library
+`HashMap` instead.
+
+*This API requires the following crate feature to be activated: `map`*
+
+## Parameters
+* `$id`: the name of the static variable. This must be used when importing with `use_symbols`.
+* `$data`: a reference to the map, such as a `&HashMap<K, V>` or `&BTreeMap<K, V>`. In general, anything
+iterable over `(&K, &V)` where `K` and `V` are `Clone`. Panics if any key occurs more than once, or if the
+key and value types can't be determined (as when the map is empty).
+* `$phf_path` (optional): the path via which the generated code refers to the `phf` crate, as for
+[`MapBuilder::with_phf_path`]. Defaults to `\"rustifact::internal::phf\"`.
+
+## Example
+build.rs
+```no_run
+use rustifact::ToTokenStream;
+use std::collections::HashMap;
+
+fn main() {
+    let ports = HashMap::from([(\"http\", 80u16), (\"https\", 443u16)]);
+    rustifact::write_phf_map_from!(PORTS, &ports);
+}
+```
+
+src/main.rs
+```no_run
+use rustifact::Map;
+
+rustifact::use_symbols!(PORTS);
+// PORTS has type Map<&'static str, u16>
+
+fn main() {
+    assert!(PORTS.get(\"https\") == Some(&443));
+}
+```"]
+#[cfg(feature = "map")]
+#[macro_export]
+macro_rules! write_phf_map_from {
+    ($id:ident, $data:expr) => {
+        rustifact::write_phf_map_from!($id, $data, "rustifact::internal::phf");
+    };
+    ($id:ident, $data:expr, $phf_path:expr) => {
+        let mut entries: Vec<(_, _)> = std::iter::IntoIterator::into_iter($data)
+            .map(|(k, v)| (std::clone::Clone::clone(k), std::clone::Clone::clone(v)))
+            .collect();
+        rustifact::internal::sort_by_tokens(&mut entries, |(k, _)| k.to_tok_stream());
+        let (k, v) = rustifact::internal::entry_types(&entries);
+        let mut builder = rustifact::MapBuilder::with_phf_path($phf_path);
+        for (key, value) in entries {
+            builder.entry(key, value);
+        }
+        rustifact::__write_with_internal!(
+            static,
+            $id,
+            rustifact::internal::quote! { rustifact::Map<#k, #v> },
+            builder.to_tok_stream()
+        );
+    };
+}
+
+#[doc = "Write a getter function returning a standard library `HashMap` with the entries of a map.
+
+The counterpart of `write_phf_map_from!`, accepting the same input. The key and value types are determined
+from the entries, so the function returns `std::collections::HashMap<K, V>` for the emitted key and value
+types. The map is built on each call, so prefer `write_phf_map_from!` for frequent lookups.
+
+## Parameters
+* `$id`: the name of the getter function. This must be used when importing with `use_symbols`.
+* `$data`: a reference to the map, such as a `&HashMap<K, V>` or `&BTreeMap<K, V>`. In general, anything
+iterable over `(&K, &V)` where `K` and `V` are `Clone`. Panics if the key and value types can't be
+determined (as when the map is empty).
+
+## Example
+build.rs
+```no_run
+use rustifact::ToTokenStream;
+use std::collections::BTreeMap;
+
+fn main() {
+    let ports = BTreeMap::from([(\"http\", 80u16), (\"https\", 443u16)]);
+    rustifact::write_std_map_fn!(get_ports, &ports);
+}
+```
+
+src/main.rs
+```no_run
+rustifact::use_symbols!(get_ports);
+// get_ports has return type std::collections::HashMap<&'static str, u16>
+
+fn main() {
+    assert!(get_ports()[\"https\"] == 443);
+}
+```"]
+#[macro_export]
+macro_rules! write_std_map_fn {
+    ($id:ident, $data:expr) => {
+        let entries: Vec<(_, _)> = std::iter::IntoIterator::into_iter($data)
+            .map(|(k, v)| (std::clone::Clone::clone(k), std::clone::Clone::clone(v)))
+            .collect();
+        let (k, v) = rustifact::internal::entry_types(&entries);
+        let map: std::collections::HashMap<_, _> = entries.into_iter().collect();
+        rustifact::__write_fn_with_internal!(
+            dummy,
+            $id,
+            rustifact::internal::quote! { std::collections::HashMap<#k, #v> },
+            map.to_tok_stream()
+        );
+    };
+}
+
+#[doc = "Write a static `Set` from the elements of a standard library set.
+
+Builds a [`SetBuilder`] from `$data` and writes it via `write_static!`, saving the boilerplate of adding
+each element by hand.
+
+*This API requires the following crate feature to be activated: `set`*
+
+## Parameters
+* `$id`: the name of the static variable. This must be used when importing with `use_symbols`.
+* `$t`: the type of the static variable, such as `Set<&'static str>`.
+* `$data`: a reference to the set, such as a `&HashSet<T>` or `&BTreeSet<T>`. In general, anything
+iterable over `&T` where `T` is `Clone`. Panics if any element occurs more than once. Elements are sorted
+first, so the output is identical between builds, whatever the iteration order of `$data`.
+
+## Example
+build.rs
+```no_run
+use rustifact::ToTokenStream;
+use std::collections::BTreeSet;
+
+fn main() {
+    let keywords: BTreeSet<&'static str> = [\"fn\", \"let\", \"match\"].into_iter().collect();
+    rustifact::write_phf_set!(KEYWORDS, Set<&'static str>, &keywords);
+}
+```
+
+src/main.rs
+```no_run
+use rustifact::Set;
+
+rustifact::use_symbols!(KEYWORDS);
+
+fn main() {
+    assert!(KEYWORDS.contains(\"let\"));
+}
+```"]
+#[cfg(feature = "set")]
+#[macro_export]
+macro_rules! write_phf_set {
+    ($id:ident, $t:ty, $data:expr) => {
+        let mut elements: Vec<_> = std::iter::IntoIterator::into_iter($data)
+            .map(std::clone::Clone::clone)
+            .collect();
+        rustifact::internal::sort_by_tokens(&mut elements, |e| e.to_tok_stream());
+        let builder: rustifact::SetBuilder<_> = elements.into_iter().collect();
+        rustifact::write_static!($id, $t, &builder);
+    };
+}
+
+#[doc = "Write a static slice.
+
+Makes the slice available for import into the main crate via `use_symbols`. Unlike [`write_static_array`],
+the exported symbol has type `&'static [$t]`, so its type doesn't depend on the length of the data.
+
+## Parameters
+* `$id`: the name of the static variable. This must be used when importing with `use_symbols`.
+* `$t`: the type of elements of the slice.
+* `$data`: the contents of the slice. May be a `Vec`, an array, an array reference, or array slice.
+
+## Example
+build.rs
+ ```no_run
+use rustifact::ToTokenStream;
+
+fn main() {
+    let primes: Vec<u32> = vec![2, 3, 5, 7, 11];
+    rustifact::write_slice!(PRIMES, u32, &primes);
+}
+```
+
+src/main.rs
+```no_run
+rustifact::use_symbols!(PRIMES);
+// The above line is equivalent to the declaration:
+// static PRIMES: &'static [u32] = &[2, 3, 5, 7, 11];
+
+fn main() {
+    assert!(PRIMES.len() == 5);
+}
+```"]
+#[macro_export]
+macro_rules! write_slice {
+    ($id:ident, $t:ty, $data:expr) => {
+        let data = $data;
+        let slice_toks = (&data[..]).to_tok_stream();
+        rustifact::__write_with_internal!(
+            static,
+            $id,
+            rustifact::internal::quote! { &'static [$t] },
+            rustifact::internal::quote! { &#slice_toks }
+        );
+    };
+}
+
+#[doc = "Write a static slice from an iterator, streaming elements to disk.
+
+Makes the slice available for import into the main crate via `use_symbols`. As with [`write_slice`], the
+exported symbol has type `&'static [$t]`. Each element is written to disk as soon as it's produced by the
+iterator, so the data (and its tokens) need never be held in memory all at once. This suits very large
+exports.
+
+## Parameters
+* `$id`: the name of the static variable. This must be used when importing with `use_symbols`.
+* `$t`: the type of elements of the slice.
+* `$data`: an iterator (or any type implementing `IntoIterator`) over the elements of the slice.
+
+## Further notes
+* Must be called from a build script (build.rs) only.
+* The generated code isn't pretty-printed or checked for syntax errors by the build script.
+
+## Example
+build.rs
+ ```no_run
+fn square(i: u64) -> u64 {
+    i * i
+}
+
+fn main() {
+    let squares = (0..1_000_000u64).map(square);
+    rustifact::write_slice_streaming!(SQUARES, u64, squares);
+}
+```
+
+src/main.rs
+```no_run
+rustifact::use_symbols!(SQUARES);
+// The above line is equivalent to the declaration:
+// static SQUARES: &'static [u64] = &[0, 1, 4, /* ... */];
+```"]
+#[macro_export]
+macro_rules! write_slice_streaming {
+    ($id:ident, $t:ty, $data:expr) => {{
+        let path_str = rustifact::__path_from_id!($id, private);
+        rustifact::internal::write_slice_streaming(
+            &path_str,
+            stringify!($id),
+            &rustifact::internal::quote! { $t },
+            $data,
+        );
+    }};
+}
+
+#[doc = "Write a one-dimensional array of numbers to a static context, in packed binary form.
+
+Makes the array available for import into the main crate via `use_symbols`. Rather than emitting an array
+literal, the elements are written to a binary file in `OUT_DIR` which is embedded with `include_bytes!`,
+and reinterpreted as the array with `transmute`. For large arrays, this is much quicker to compile than
+the equivalent [`write_static_array`]: with a million `u32` elements, rustc took about 0.1 seconds to
+compile the packed array, against 2.5 seconds for the array literal.
+
+## Parameters
+* `$id`: the name of the static array. This must be used when importing with `use_symbols`.
+* `$t`: the type of elements of the array, which must implement [`PackedElement`].
+* `$data`: the contents of the array. May be a `Vec`, an array, an array reference, or array slice.
+
+## Further notes
+* Must be called from a build script (build.rs) only.
+* Elements are stored in the byte order of the target platform (per `CARGO_CFG_TARGET_ENDIAN`), so the
+result is correct when cross-compiling. As the bytes are copied into the array by value, the alignment of
+the embedded bytes is irrelevant.
+
+## Example
+build.rs
+ ```no_run
+fn main() {
+    let table: Vec<u32> = (0..100_000).map(|i| i * 3).collect();
+    rustifact::write_static_array_packed!(TABLE, u32, &table);
+}
+```
+
+src/main.rs
+```no_run
+rustifact::use_symbols!(TABLE);
+// The above line is equivalent to the declaration:
+// static TABLE: [u32; 100000] = [0, 3, 6, /* ... */];
+
+fn main() {
+    assert!(TABLE[10] == 30);
+}
+```"]
+#[macro_export]
+macro_rules! write_static_array_packed {
+    ($id:ident, $t:ty, $data:expr) => {{
+        let data = $data;
+        let bytes = rustifact::internal::pack::<$t>(&data[..]);
+        let bin_name = format!(
+            "/{}rustifact_{}_{}.bin",
+            rustifact::internal::namespace_dir(),
+            std::env::var("CARGO_PKG_NAME").unwrap(),
+            stringify!($id),
+        );
+        let bin_path_str = format!("{}{}", rustifact::internal::generated_dir(), bin_name);
+        rustifact::internal::write_file(&bin_path_str, &bytes).unwrap();
+        let len = data.len();
+        let byte_len = bytes.len();
+        let tokens = rustifact::internal::quote! {
+            // Sound, as PackedElement is only implemented for types valid for any bit pattern.
+            static $id: [$t; #len] = unsafe {
+                std::mem::transmute::<[u8; #byte_len], [$t; #len]>(
+                    *include_bytes!(concat!(env!("OUT_DIR"), "/rustifact", #bin_name))
+                )
+            };
+        };
+        rustifact::__write_tokens_with_internal!($id, private, tokens);
+    }};
+}
+
+#[doc = "Write a byte blob in compressed form, with an accessor function that decompresses it.
+
+Makes the static variable and its accessor available for import into the main crate via `use_symbols`.
+The bytes are compressed with DEFLATE (via [miniz_oxide](https://crates.io/crates/miniz_oxide)) at build
+time, written to a binary file in `OUT_DIR`, and embedded with `include_bytes!`. The variable is a
+`std::sync::OnceLock<Vec<u8>>`, and the accessor (named as the variable, in lowercase) decompresses the
+bytes on first use, returning them as a `&'static [u8]`.
+
+*This API requires the following crate feature to be activated: `compress`*
+
+## Parameters
+* `$id`: the name of the static variable. This must be used when importing with `use_symbols`.
+* `$data`: the bytes to compress. May be a `Vec<u8>`, an array, an array reference, or array slice.
+
+## Further notes
+* Must be called from a build script (build.rs) only.
+* The main crate must also depend on *Rustifact* with the `compress` feature, which provides the
+decompressor.
+* Decompression takes place at runtime, on the first call to the accessor. It costs time linear in the
+size of the data (on the order of milliseconds per megabyte), and allocates the decompressed bytes on the
+heap, where they remain for the life of the program. Compression suits large, compressible assets that
+aren't needed during constant evaluation; use [`write_static_array_packed`] otherwise.
+
+## Example
+build.rs
+ ```no_run
+fn main() {
+    let table: Vec<u8> = (0..1_000_000u32).map(|i| (i % 7) as u8).collect();
+    rustifact::write_compressed_bytes!(TABLE, &table);
+}
+```
+
+src/main.rs
+```no_run
+rustifact::use_symbols!(TABLE);
+// The above line is equivalent to the declarations:
+// static TABLE: std::sync::OnceLock<Vec<u8>> = std::sync::OnceLock::new();
+// fn table() -> &'static [u8] {
+//     TABLE.get_or_init(|| /* the bytes, decompressed */)
+// }
+
+fn main() {
+    assert!(table()[8] == 1);
+}
+```"]
+#[cfg(feature = "compress")]
+#[macro_export]
+macro_rules! write_compressed_bytes {
+    ($id:ident, $data:expr) => {{
+        let data = $data;
+        let bytes: &[u8] = &data[..];
+        let compressed = rustifact::internal::deflate(bytes);
+        let bin_name = format!(
+            "/{}rustifact_{}_{}.deflate",
+            rustifact::internal::namespace_dir(),
+            std::env::var("CARGO_PKG_NAME").unwrap(),
+            stringify!($id),
+        );
+        let bin_path_str = format!("{}{}", rustifact::internal::generated_dir(), bin_name);
+        rustifact::internal::write_file(&bin_path_str, &compressed).unwrap();
+        let len = bytes.len();
+        let accessor = rustifact::internal::format_ident!("{}", stringify!($id).to_lowercase());
+        let tokens = rustifact::internal::quote! {
+            static $id: std::sync::OnceLock<Vec<u8>> = std::sync::OnceLock::new();
+            fn #accessor() -> &'static [u8] {
+                $id.get_or_init(|| {
+                    let compressed = include_bytes!(concat!(env!("OUT_DIR"), "/rustifact", #bin_name));
+                    rustifact::internal::inflate(compressed, #len)
+                })
+            }
+        };
+        rustifact::__write_tokens_with_internal!($id, private, tokens);
+    }};
+}
+
+#[doc = "Write an array of any dimension to a static context.
+
+Makes the array available for import into the main crate via `use_symbols`. Whereas [`write_static_array`]
+supports up to 16 dimensions, `write_deep_array!` converts the data recursively at build time, so supports
+any dimension. Nested arrays (or vecs) of the same dimension must have the same length.
+
+## Parameters
+* `$id`: the name/identifier to give the exported array.
+* `$t`: the type of elements of the exported array will contain, followed by `: DIM` where `DIM` is the
+dimension (1, 2, 3, ...) of the array.
+* `$data`: the contents of the array. May be nested arrays, slices or vecs.
+
+## Further notes
+* Must be called from a build script (build.rs) only.
+
+## Example
+build.rs
+ ```no_run
+use rustifact::ToTokenStream;
+
+fn main() {
+    let tensor = vec![vec![vec![1u8, 2], vec![3, 4]], vec![vec![5, 6], vec![7, 8]]];
+    rustifact::write_deep_array!(TENSOR, u8 : 3, &tensor);
+}
+```
+
+src/main.rs
+```no_run
+rustifact::use_symbols!(TENSOR);
+// The above line is equivalent to the declaration:
+// static TENSOR: [[[u8; 2]; 2]; 2] = [[[1, 2], [3, 4]], [[5, 6], [7, 8]]];
+```"]
+#[macro_export]
+macro_rules! write_deep_array {
+    ($id:ident, $t:ty : $dim:expr, $data:expr) => {
+        let data = $data;
+        let dim: usize = $dim;
+        let leaf_type = rustifact::internal::quote! { $t };
+        let (tokens_data, arr_type) =
+            rustifact::internal::deep_array(stringify!($id), data.to_tok_stream(), dim, &leaf_type);
+        let tokens_data =
+            rustifact::internal::compress_repeats(tokens_data, dim, leaf_type.clone());
+        let tokens_data = rustifact::internal::dedup_array(tokens_data, dim, leaf_type);
+        rustifact::__write_with_internal!(static, $id, arr_type, tokens_data);
+    };
+}
+
+#[doc = "Write a constant variable.
+
+Makes the constant available for import into the main crate via `use_symbols`.
+
+## Parameters
+* `$id`: the name of the constant. This must be used when importing with `use_symbols`.
+* `$t`: the type of the constant.
+* `$data`: the data to assign to the constant. Must be representable on the stack. Heap-allocated data
+(such as a `Vec`) is rejected with a panic in the build script; use `write_fn!` for such data.
+* `$attrs` (optional): a list of attributes, such as `#[doc = \"...\"]` or `#[deprecated]`, to place on
+the declaration. Has type `&[S]` where S is String or &str.
+
+## Example
+build.rs
+ ```no_run
+use rustifact::ToTokenStream;
+
+fn main() {
+    let meaning_of_life = Some(42);
+    rustifact::write_const!(MEANING_OF_LIFE, Option<i32>, meaning_of_life);
+}
+```
+
+src/main.rs
+```no_run
+rustifact::use_symbols!(MEANING_OF_LIFE);
+// The above line is equivalent to the declaration:
+// const MEANING_OF_LIFE: Option<i32> = Some(42);
+
+fn main() {
+    if let Some(mean) = MEANING_OF_LIFE {
+        println!(\"The meaning of life is {}\", mean);
+    } else {
+        println!(\"Life has no meaning.\");
+    }
+}
+```"]
+#[macro_export]
+macro_rules! write_const {
+    ($id:ident, $t:ty, $data:expr) => {
+        let data = $data;
+        rustifact::internal::check_const_safe(stringify!($id), &data);
+        rustifact::__write_with_internal!(
+            const,
+            $id,
+            rustifact::internal::quote! { $t },
+            data.to_tok_stream()
+        );
+    };
+    ($id:ident, $t:ty, $data:expr, $attrs:expr) => {
+        let data = $data;
+        rustifact::internal::check_const_safe(stringify!($id), &data);
+        rustifact::__write_with_internal!(
+            const,
+            $id,
+            rustifact::internal::quote! { $t },
+            data.to_tok_stream(),
+            rustifact::internal::parse_attributes($attrs)
+        );
+    };
+}
+
+#[doc = "Write a constant variable, storing its value in a separate file.
+
+Makes the constant available for import into the main crate via `use_symbols`. Identical to `write_const!`,
+except that the value is written to its own file and included by the declaration, which keeps the
+generated code for the constant readable when the value is large.
+
+## Parameters
+* `$id`: the name of the constant. This must be used when importing with `use_symbols`.
+* `$t`: the type of the constant.
+* `$data`: the data to assign to the constant. Must be representable on the stack.
+
+## Example
+build.rs
+ ```no_run
+use rustifact::ToTokenStream;
+
+fn main() {
+    let squares: [u32; 1000] = std::array::from_fn(|i| (i * i) as u32);
+    rustifact::write_const_include!(SQUARES, [u32; 1000], &squares);
+}
+```
+
+src/main.rs
+```no_run
+rustifact::use_symbols!(SQUARES);
+// The above line is equivalent to the declaration:
+// const SQUARES: [u32; 1000] = include!(\"<OUT_DIR>/rustifact/rustifact_<package>_SQUARES_value.rs\");
+//
+// where the included file holds the expression [0u32, 1u32, 4u32, ...].
+```"]
+#[macro_export]
+macro_rules! write_const_include {
+    ($id:ident, $t:ty, $data:expr) => {
+        let data = $data;
+        rustifact::internal::check_const_safe(stringify!($id), &data);
+        let id_value = format!("{}_value", stringify!($id));
+        rustifact::__write_tokens_with_internal_raw!(id_value, data.to_tok_stream());
+        let include_path = format!(
+            "/{}rustifact_{}_{}.rs",
+            rustifact::internal::namespace_dir(),
+            std::env::var("CARGO_PKG_NAME").unwrap(),
+            id_value
+        );
+        rustifact::__write_with_internal!(
+            const,
+            $id,
+            rustifact::internal::quote! { $t },
+            rustifact::internal::quote! { include!(concat!(env!("OUT_DIR"), "/rustifact", #include_path)) }
+        );
+    };
+}
+
+#[doc = "Write a constant variable with unsuffixed numeric literals.
+
+Makes the constant available for import into the main crate via `use_symbols`. Identical to `write_const!`,
+except that numeric literals are emitted without type suffixes (`5` rather than `5usize`), so their types
+are inferred from `$t`. This allows, for example, a `usize` generated by the build script to populate a `u64`
+field.
+
+## Parameters
+* `$id`: the name of the constant. This must be used when importing with `use_symbols`.
+* `$t`: the type of the constant.
+* `$data`: the data to assign to the constant. Must be representable on the stack.
+
+## Example
+build.rs
+ ```no_run
+use rustifact::ToTokenStream;
+
+fn main() {
+    let sizes: [usize; 3] = [1, 2, 3];
+    rustifact::write_const_unsuffixed!(SIZES, [u64; 3], sizes);
+}
+```
+
+src/main.rs
+```no_run
+rustifact::use_symbols!(SIZES);
+// The above line is equivalent to the declaration:
+// const SIZES: [u64; 3] = [1, 2, 3];
+```"]
+#[macro_export]
+macro_rules! write_const_unsuffixed {
+    ($id:ident, $t:ty, $data:expr) => {
+        let data = $data;
+        rustifact::internal::check_const_safe(stringify!($id), &data);
+        let mut tokens_data = rustifact::internal::TokenStream::new();
+        data.to_toks_unsuffixed(&mut tokens_data);
+        rustifact::__write_with_internal!(
+            const,
+            $id,
+            rustifact::internal::quote! { $t },
+            tokens_data
+        );
+    };
+}
+
+#[doc = "Write a static byte slice.
+
+Makes the byte slice available for import into the main crate via `use_symbols`. The bytes are emitted as a
+single byte string literal (`b\"...\"`) rather than an array of `u8` literals, which keeps the generated
+file compact and quick to compile, even for large blobs.
+
+## Parameters
+* `$id`: the name of the static variable, having type `&'static [u8]`. This must be used when importing with
+`use_symbols`.
+* `$data`: the bytes to export. May be any type implementing `AsRef<[u8]>`, such as `Vec<u8>` or `&[u8]`.
+
+## Example
+build.rs
+ ```no_run
+fn main() {
+    let bytes: Vec<u8> = (0..=255).collect();
+    rustifact::write_bytes!(ALL_BYTES, &bytes);
+}
+```
+
+src/main.rs
+```no_run
+rustifact::use_symbols!(ALL_BYTES);
+// The above line is equivalent to the declaration:
+// static ALL_BYTES: &'static [u8] = b\"\\x00\\x01...\\xff\";
+
+fn main() {
+    assert!(ALL_BYTES.len() == 256);
+}
+```"]
+#[macro_export]
+macro_rules! write_bytes {
+    ($id:ident, $data:expr) => {
+        let data = $data;
+        rustifact::__write_with_internal!(
+            static,
+            $id,
+            rustifact::internal::quote! { &'static [u8] },
+            rustifact::internal::byte_string(&data)
+        );
+    };
+}
+
+#[doc = "Write a getter function for a heap-allocated variable.
+
+Makes the getter function available for import into the main crate via `use_symbols`.
+
+## Parameters
+* `$id`: the name of the getter function. This must be used when importing with `use_symbols`.
+* `$t`: the return type of the getter function.
+* `$data`: the data to return from the geter function.
+
+## Example
+build.rs
+ ```no_run
+use rustifact::ToTokenStream;
+
+fn main() {
+    let vecs = vec![vec![1, 2], vec![1, 2, 3], vec![1, 2, 3, 4]];
+    rustifact::write_fn!(get_vecs, Vec<Vec<u32>>, vecs);
+}
+```
+
+src/main.rs
+```no_run
+rustifact::use_symbols!(get_vecs);
+// The above line is equivalent to the declaration:
+// fn get_vecs() -> Vec<Vec<u32>> {
+//     vec![vec![1, 2], vec![1, 2, 3], vec![1, 2, 3, 4]]
+// }
+
+fn main() {
+    println!(\"{:?}\", get_vecs());
+}
+```"]
+#[macro_export]
+macro_rules! write_fn {
+    ($id:ident, $t:ty, $data:expr) => {
+        let data = $data;
+        rustifact::__write_fn_with_internal!(
+            dummy,
+            $id,
+            rustifact::internal::quote! { $t },
+            data.to_tok_stream()
+        );
+    };
+}
+
+#[doc = "Write a const function returning data.
+
+Makes the function available for import into the main crate via `use_symbols`. Unlike `write_fn!`, the
+function is a `const fn`, so its result is usable in const contexts, such as the initialiser of a `const`
+or `static`, or an array length.
+
+## Parameters
+* `$id`: the name of the function. This must be used when importing with `use_symbols`.
+* `$t`: the return type of the function.
+* `$data`: the data to return from the function. Must be constructible in a const context. Heap-allocated
+data (such as a `Vec`) is rejected with a panic in the build script; use `write_fn!` for such data.
+
+## Example
+build.rs
+ ```no_run
+use rustifact::ToTokenStream;
+
+fn main() {
+    rustifact::write_const_fn!(block_sizes, [usize; 3], &[16usize, 32, 64]);
+}
+```
+
+src/main.rs
+```no_run
+rustifact::use_symbols!(block_sizes);
+// The above line is equivalent to the declaration:
+// const fn block_sizes() -> [usize; 3] {
+//     [16usize, 32usize, 64usize]
+// }
+
+const LARGEST: usize = block_sizes()[2];
+
+fn main() {
+    let buffer = [0u8; LARGEST];
+    println!(\"{}\", buffer.len());
+}
+```"]
+#[macro_export]
+macro_rules! write_const_fn {
+    ($id:ident, $t:ty, $data:expr) => {
+        let data = $data;
+        rustifact::internal::check_const_safe(stringify!($id), &data);
+        rustifact::__write_fn_with_internal!(
+            const,
+            $id,
+            rustifact::internal::quote! { $t },
+            data.to_tok_stream()
+        );
+    };
+}
+
+#[doc = "Write a thread-local variable.
+
+Makes the thread-local variable available for import into the main crate via `use_symbols`. Unlike
+`write_static!`, the data needn't be constructible in a const context, so heap-allocated data (such as
+a `Vec` or `HashMap`) is supported. The data is constructed on first access from each thread, and then
+reused, whereas `write_fn!` constructs the data on every call.
+
+## Parameters
+* `$id`: the name of the thread-local variable. This must be used when importing with `use_symbols`.
+* `$t`: the type of the variable.
+* `$data`: the data to assign to the variable.
+
+## Example
+build.rs
+ ```no_run
+use rustifact::ToTokenStream;
+
+fn main() {
+    let names = vec![\"alice\".to_string(), \"bob\".to_string()];
+    rustifact::write_thread_local!(NAMES, Vec<&'static str>, &names);
+}
+```
+
+src/main.rs
+```no_run
+rustifact::use_symbols!(NAMES);
+// The above line is equivalent to the declaration:
+// thread_local! {
+//     static NAMES: Vec<&'static str> = vec![\"alice\", \"bob\"];
+// }
+
+fn main() {
+    NAMES.with(|names| println!(\"{:?}\", names));
+}
+```"]
+#[macro_export]
+macro_rules! write_thread_local {
+    ($id:ident, $t:ty, $data:expr) => {
+        let data = $data;
+        let tokens_data = data.to_tok_stream();
+        let tokens = rustifact::internal::quote! {
+            thread_local! {
+                static $id: $t = #tokens_data;
+            }
+        };
+        rustifact::__write_tokens_with_internal!($id, private, tokens);
+    };
+}
+
+#[doc = "Write a lazily initialised static variable, with an accessor function.
+
+Makes the static variable and its accessor available for import into the main crate via `use_symbols`.
+The variable is a `std::sync::OnceLock`, and the accessor (named as the variable, in lowercase) constructs
+the data on first use. Unlike `write_fn!`, the data is constructed only once, which suits large
+heap-allocated data that's accessed many times.
+
+## Parameters
+* `$id`: the name of the static variable. This must be used when importing with `use_symbols`.
+* `$t`: the type of the data.
+* `$data`: the data to assign to the variable.
+
+## Example
+build.rs
+ ```no_run
+use rustifact::ToTokenStream;
+
+fn main() {
+    let primes = vec![2u32, 3, 5, 7, 11];
+    rustifact::write_lazy!(PRIMES, Vec<u32>, &primes);
+}
+```
+
+src/main.rs
+```no_run
+rustifact::use_symbols!(PRIMES);
+// The above line is equivalent to the declarations:
+// static PRIMES: std::sync::OnceLock<Vec<u32>> = std::sync::OnceLock::new();
+// fn primes() -> &'static Vec<u32> {
+//     PRIMES.get_or_init(|| vec![2u32, 3u32, 5u32, 7u32, 11u32])
+// }
+
+fn main() {
+    println!(\"{:?}\", primes());
+}
+```"]
+#[macro_export]
+macro_rules! write_lazy {
     ($id:ident, $t:ty, $data:expr) => {
         let data = $data;
-        rustifact::__write_with_internal!(
-            static,
-            $id,
-            rustifact::internal::quote! { $t },
-            data.to_tok_stream()
-        );
+        let tokens_data = data.to_tok_stream();
+        let accessor = rustifact::internal::format_ident!("{}", stringify!($id).to_lowercase());
+        let tokens = rustifact::internal::quote! {
+            static $id: std::sync::OnceLock<$t> = std::sync::OnceLock::new();
+            fn #accessor() -> &'static $t {
+                $id.get_or_init(|| #tokens_data)
+            }
+        };
+        rustifact::__write_tokens_with_internal!($id, private, tokens);
     };
 }
 
-#[doc = "Write a constant variable.
+#[doc = "Write a function returning a reference to lazily initialised data.
 
-Makes the constant available for import into the main crate via `use_symbols`.
+Makes the function available for import into the main crate via `use_symbols`. Unlike `write_fn!`, which
+reconstructs the data on every call, the data is constructed on the first call only, and held in a
+`std::sync::OnceLock` local to the function. Each call returns a reference to the same data.
 
 ## Parameters
-* `$id`: the name of the constant. This must be used when importing with `use_symbols`.
-* `$t`: the type of the constant.
-* `$data`: the data to assign to the constant. Must be representable on the stack.
+* `$id`: the name of the function. This must be used when importing with `use_symbols`.
+* `$t`: the type of the data. The function's return type is `&'static $t`.
+* `$data`: the data returned (by reference) by the function.
 
 ## Example
 build.rs
@@ -562,46 +2224,51 @@ build.rs
 use rustifact::ToTokenStream;
 
 fn main() {
-    let meaning_of_life = Some(42);
-    rustifact::write_const!(MEANING_OF_LIFE, Option<i32>, meaning_of_life);
+    let names = vec![\"alpha\", \"beta\", \"gamma\"];
+    rustifact::write_fn_ref!(get_names, Vec<&'static str>, &names);
 }
 ```
 
 src/main.rs
 ```no_run
-rustifact::use_symbols!(MEANING_OF_LIFE);
+rustifact::use_symbols!(get_names);
 // The above line is equivalent to the declaration:
-// const MEANING_OF_LIFE: Option<i32> = Some(42);
+// fn get_names() -> &'static Vec<&'static str> {
+//     static DATA: std::sync::OnceLock<Vec<&'static str>> = std::sync::OnceLock::new();
+//     DATA.get_or_init(|| vec![\"alpha\", \"beta\", \"gamma\"])
+// }
 
 fn main() {
-    if let Some(mean) = MEANING_OF_LIFE {
-        println!(\"The meaning of life is {}\", mean);
-    } else {
-        println!(\"Life has no meaning.\");
-    }
+    assert!(get_names()[1] == \"beta\");
 }
 ```"]
 #[macro_export]
-macro_rules! write_const {
+macro_rules! write_fn_ref {
     ($id:ident, $t:ty, $data:expr) => {
         let data = $data;
-        rustifact::__write_with_internal!(
-            const,
-            $id,
-            rustifact::internal::quote! { $t },
-            data.to_tok_stream()
-        );
+        let tokens_data = data.to_tok_stream();
+        let tokens = rustifact::internal::quote! {
+            fn $id() -> &'static $t {
+                static DATA: std::sync::OnceLock<$t> = std::sync::OnceLock::new();
+                DATA.get_or_init(|| #tokens_data)
+            }
+        };
+        rustifact::__write_tokens_with_internal!($id, private, tokens);
     };
 }
 
-#[doc = "Write a getter function for a heap-allocated variable.
+#[doc = "Write a function mapping keys to values with a `match` expression.
 
-Makes the getter function available for import into the main crate via `use_symbols`.
+Makes the function available for import into the main crate via `use_symbols`. The generated function
+takes a key and returns `Some` value for each key in the list, or `None` for any other key. This suits
+small compile-time dispatch tables, where a perfect hash map would be overkill.
 
 ## Parameters
-* `$id`: the name of the getter function. This must be used when importing with `use_symbols`.
-* `$t`: the return type of the getter function.
-* `$data`: the data to return from the geter function.
+* `$id`: the name of the function. This must be used when importing with `use_symbols`.
+* `$t_key`: the parameter type of the function.
+* `$t_val`: the type of values returned (wrapped in an `Option`) by the function.
+* `$keys_vals`: The list of type `&[(K, V)]` where K is the type of keys, which must output patterns
+(for example, integers, `char`s, `bool`s and strings) and V is the type of values.
 
 ## Example
 build.rs
@@ -609,46 +2276,81 @@ build.rs
 use rustifact::ToTokenStream;
 
 fn main() {
-    let vecs = vec![vec![1, 2], vec![1, 2, 3], vec![1, 2, 3, 4]];
-    rustifact::write_fn!(get_vecs, Vec<Vec<u32>>, vecs);
+    let keywords = vec![(\"fn\", 0u8), (\"let\", 1u8), (\"match\", 2u8)];
+    rustifact::write_match_fn!(keyword_id, &str => u8, &keywords);
 }
 ```
 
 src/main.rs
 ```no_run
-rustifact::use_symbols!(get_vecs);
+rustifact::use_symbols!(keyword_id);
 // The above line is equivalent to the declaration:
-// fn get_vecs() -> Vec<Vec<u32>> {
-//     vec![vec![1, 2], vec![1, 2, 3], vec![1, 2, 3, 4]]
+// fn keyword_id(key: &str) -> Option<u8> {
+//     match key {
+//         \"fn\" => Some(0),
+//         \"let\" => Some(1),
+//         \"match\" => Some(2),
+//         _ => None,
+//     }
 // }
 
 fn main() {
-    println!(\"{:?}\", get_vecs());
+    assert!(keyword_id(\"let\") == Some(1));
+    assert!(keyword_id(\"while\") == None);
 }
 ```"]
 #[macro_export]
-macro_rules! write_fn {
-    ($id:ident, $t:ty, $data:expr) => {
-        let data = $data;
-        rustifact::__write_fn_with_internal!(
-            dummy,
-            $id,
-            rustifact::internal::quote! { $t },
-            data.to_tok_stream()
-        );
-    };
+macro_rules! write_match_fn {
+    ($id:ident, $t_key:ty => $t_val:ty, $keys_vals:expr) => {{
+        let mut arms = rustifact::internal::TokenStream::new();
+        let keys_vals = $keys_vals;
+        for (key, val) in keys_vals.iter() {
+            let key_toks = key.to_tok_stream();
+            let val_toks = val.to_tok_stream();
+            arms.extend(rustifact::internal::quote! { #key_toks => Some(#val_toks), });
+        }
+        let tokens = rustifact::internal::quote! {
+            fn $id(key: $t_key) -> Option<$t_val> {
+                match key {
+                    #arms
+                    #[allow(unreachable_patterns)]
+                    _ => None,
+                }
+            }
+        };
+        rustifact::__write_tokens_with_internal!($id, private, tokens);
+    }};
 }
 
 #[doc(hidden)]
 #[macro_export]
 macro_rules! __write_internal {
-    ($static_const:ident, $id_group:ident, $t:ty, $public:literal, $ids_data:expr) => {{
+    ($static_const:ident, $id_group:ident, $t:ty, $vis:ident, $ids_data:expr) => {{
+        let vis = rustifact::internal::visibility(stringify!($vis));
         let mut toks = rustifact::internal::TokenStream::new();
         let ids_data = $ids_data;
-        for (id_str, data) in ids_data.iter() {
+        for (id_str, data) in ids_data {
+            if stringify!($static_const) == "const" {
+                rustifact::internal::check_const_safe(&id_str.to_string(), &data);
+            }
+            let data_toks = data.to_tok_stream();
+            let id = rustifact::internal::parse_ident(&id_str);
+            let element = rustifact::internal::quote! { #vis $static_const #id: $t = #data_toks; };
+            toks.extend(element);
+        }
+        rustifact::__write_tokens_with_internal!($id_group, private, toks);
+    }};
+    ($static_const:ident, $id_group:ident, $t:ty, $vis_ids_data:expr) => {{
+        let mut toks = rustifact::internal::TokenStream::new();
+        let vis_ids_data = $vis_ids_data;
+        for (public, id_str, data) in vis_ids_data {
+            let public: bool = *std::borrow::Borrow::<bool>::borrow(&public);
+            if stringify!($static_const) == "const" {
+                rustifact::internal::check_const_safe(&id_str.to_string(), &data);
+            }
             let data_toks = data.to_tok_stream();
-            let id = rustifact::internal::format_ident!("{}", id_str);
-            let element = if $public {
+            let id = rustifact::internal::parse_ident(&id_str);
+            let element = if public {
                 rustifact::internal::quote! { pub $static_const #id: $t = #data_toks; }
             } else {
                 rustifact::internal::quote! { $static_const #id: $t = #data_toks; }
@@ -662,12 +2364,32 @@ macro_rules! __write_internal {
 #[doc(hidden)]
 #[macro_export]
 macro_rules! __write_internal_struct {
-    ($id_struct:ident, $public:literal, $vis_ids_types:expr) => {{
+    ($id_struct:ident, $vis:ident, $vis_ids_types:expr) => {
+        rustifact::__write_internal_struct!(
+            $id_struct,
+            $vis,
+            $vis_ids_types,
+            rustifact::internal::TokenStream::new()
+        )
+    };
+    ($id_struct:ident, $vis:ident, $vis_ids_types:expr, $derives:expr) => {
+        rustifact::__write_internal_struct!(
+            $id_struct,
+            $vis,
+            $vis_ids_types,
+            $derives,
+            rustifact::internal::TokenStream::new()
+        )
+    };
+    ($id_struct:ident, $vis:ident, $vis_ids_types:expr, $derives:expr, $repr:expr) => {{
+        let vis = rustifact::internal::visibility(stringify!($vis));
+        let derives = $derives;
+        let repr = $repr;
         let mut toks = rustifact::internal::TokenStream::new();
         let vis_ids_types = $vis_ids_types;
         for (public, id_str, type_str) in vis_ids_types.iter() {
             if let Ok(t) = rustifact::internal::parse_str::<rustifact::internal::Type>(type_str) {
-                let id = rustifact::internal::format_ident!("{}", id_str);
+                let id = rustifact::internal::parse_ident(&id_str);
                 let element = if *public {
                     rustifact::internal::quote! { pub #id: #t, }
                 } else {
@@ -678,27 +2400,105 @@ macro_rules! __write_internal_struct {
                 panic!("Couldn't parse the type '{}'", type_str);
             }
         }
-        let toks_struct = if $public {
+        let toks_struct = rustifact::internal::quote! {
+            #derives
+            #repr
+            #vis struct $id_struct { #toks }
+        };
+        rustifact::__write_tokens_with_internal!($id_struct, private, toks_struct);
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __write_internal_enum {
+    ($id_enum:ident, $public:literal, $variants:expr) => {
+        rustifact::__write_internal_enum!(
+            $id_enum,
+            $public,
+            $variants,
+            rustifact::internal::TokenStream::new()
+        )
+    };
+    ($id_enum:ident, $public:literal, $variants:expr, $repr:expr) => {{
+        let mut toks = rustifact::internal::TokenStream::new();
+        let variants = $variants;
+        let repr = $repr;
+        for (id_str, spec) in variants.iter() {
+            let id = rustifact::internal::parse_ident(&id_str);
+            let fields = match spec {
+                None => rustifact::internal::TokenStream::new(),
+                Some(rustifact::FieldSpec::Tuple(types)) => {
+                    let mut fields = rustifact::internal::TokenStream::new();
+                    for type_str in types.iter() {
+                        let t = rustifact::internal::parse_type(type_str);
+                        fields.extend(rustifact::internal::quote! { #t, });
+                    }
+                    rustifact::internal::quote! { (#fields) }
+                }
+                Some(rustifact::FieldSpec::Struct(ids_types)) => {
+                    let mut fields = rustifact::internal::TokenStream::new();
+                    for (field_str, type_str) in ids_types.iter() {
+                        let field = rustifact::internal::parse_ident(&field_str);
+                        let t = rustifact::internal::parse_type(type_str);
+                        fields.extend(rustifact::internal::quote! { #field: #t, });
+                    }
+                    rustifact::internal::quote! { { #fields } }
+                }
+                Some(rustifact::FieldSpec::Discriminant(expr_str)) => {
+                    let expr = rustifact::internal::parse_expr(expr_str);
+                    rustifact::internal::quote! { = #expr }
+                }
+            };
+            toks.extend(rustifact::internal::quote! { #id #fields, });
+        }
+        let toks_enum = if $public {
             rustifact::internal::quote! {
-                pub struct $id_struct { #toks }
+                #repr
+                pub enum $id_enum { #toks }
             }
         } else {
             rustifact::internal::quote! {
-               struct $id_struct { #toks }
+                #repr
+                enum $id_enum { #toks }
             }
         };
-        rustifact::__write_tokens_with_internal!($id_struct, private, toks_struct);
+        rustifact::__write_tokens_with_internal!($id_enum, private, toks_enum);
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __write_internal_type_alias {
+    ($id_alias:ident, $public:literal, $type_str:expr) => {{
+        let t = rustifact::internal::parse_type(&$type_str);
+        let toks_alias = if $public {
+            rustifact::internal::quote! { pub type $id_alias = #t; }
+        } else {
+            rustifact::internal::quote! { type $id_alias = #t; }
+        };
+        rustifact::__write_tokens_with_internal!($id_alias, private, toks_alias);
     }};
 }
 
 #[doc(hidden)]
 #[macro_export]
 macro_rules! __write_internal_struct_uniform {
-    ($id_struct:ident, $t:ty, $public:literal, $vis_ids:expr) => {{
+    ($id_struct:ident, $t:ty, $public:literal, $vis_ids:expr) => {
+        rustifact::__write_internal_struct_uniform!(
+            $id_struct,
+            $t,
+            $public,
+            $vis_ids,
+            rustifact::internal::TokenStream::new()
+        )
+    };
+    ($id_struct:ident, $t:ty, $public:literal, $vis_ids:expr, $derives:expr) => {{
+        let derives = $derives;
         let mut toks = rustifact::internal::TokenStream::new();
         let vis_ids = $vis_ids;
         for (public, id_str) in vis_ids.iter() {
-            let id = rustifact::internal::format_ident!("{}", id_str);
+            let id = rustifact::internal::parse_ident(&id_str);
             let element = if *public {
                 rustifact::internal::quote! { pub #id: $t, }
             } else {
@@ -708,14 +2508,20 @@ macro_rules! __write_internal_struct_uniform {
         }
         let toks_struct = if $public {
             rustifact::internal::quote! {
+                #derives
                 pub struct $id_struct { #toks }
             }
         } else {
             rustifact::internal::quote! {
-               struct $id_struct { #toks }
+                #derives
+                struct $id_struct { #toks }
             }
         };
         rustifact::__write_tokens_with_internal!($id_struct, private, toks_struct);
+        rustifact::internal::record_fields(
+            &rustifact::__path_from_id!($id_struct, private),
+            vis_ids.iter().map(|(_, id_str)| id_str),
+        );
     }};
 }
 
@@ -725,8 +2531,14 @@ macro_rules! __write_internal_struct_uniform_init {
     ($id_struct:ident, $id_exps:ident, $t:ty, $ids_exps:expr) => {{
         let mut toks = rustifact::internal::TokenStream::new();
         let ids_exps = $ids_exps;
+        rustifact::internal::check_fields(
+            concat!(stringify!($id_struct), "_", stringify!($id_exps)),
+            stringify!($id_struct),
+            &rustifact::__path_from_id!($id_struct, private),
+            ids_exps.iter().map(|(id_str, _)| id_str),
+        );
         for (id_str, exp) in ids_exps.iter() {
-            let id = rustifact::internal::format_ident!("{}", id_str);
+            let id = rustifact::internal::parse_ident(&id_str);
             let exp_toks = exp.to_tok_stream();
             toks.extend(rustifact::internal::quote! { #id: #exp_toks, });
         }
@@ -742,20 +2554,30 @@ macro_rules! __write_internal_struct_uniform_init {
     }};
 }
 
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __write_internal_impl {
+    ($id_type:ident, $sigs_bodies:expr) => {{
+        let methods = rustifact::internal::parse_methods($sigs_bodies);
+        let id_impl = format!("{}_impl", stringify!($id_type));
+        let toks_impl = rustifact::internal::quote! {
+            impl $id_type { #methods }
+        };
+        rustifact::__write_tokens_with_internal_raw!(id_impl, toks_impl);
+    }};
+}
+
 #[doc(hidden)]
 #[macro_export]
 macro_rules! __write_internal_fns {
-    ($id_group:ident, $t:ty, $public:literal, $ids_data:expr) => {{
+    ($id_group:ident, $t:ty, $vis:ident, $ids_data:expr) => {{
+        let vis = rustifact::internal::visibility(stringify!($vis));
         let mut toks = rustifact::internal::TokenStream::new();
         let ids_data = $ids_data;
-        for (id_str, data) in ids_data.iter() {
+        for (id_str, data) in ids_data {
             let data_toks = data.to_tok_stream();
-            let id = rustifact::internal::format_ident!("{}", id_str);
-            let element = if $public {
-                rustifact::internal::quote! { pub fn #id() -> $t {#data_toks} }
-            } else {
-                rustifact::internal::quote! { fn #id() -> $t {#data_toks} }
-            };
+            let id = rustifact::internal::parse_ident(&id_str);
+            let element = rustifact::internal::quote! { #vis fn #id() -> $t {#data_toks} };
             toks.extend(element);
         }
         rustifact::__write_tokens_with_internal!($id_group, private, toks);
@@ -767,12 +2589,17 @@ macro_rules! __write_internal_fns {
 Makes the static variables available for import into the main crate via `use_symbols`.
 
 ## Parameters
-* `public` or `private`: whether to make the variables publicly visible after import with `use_symbols`.
+* `public`, `pub_crate` or `private`: the visibility (`pub`, `pub(crate)` or none) of the variables after import
+with `use_symbols`.
 * `$id_group`: the group alias by which these variables are referred when importing with `use_symbols`.
 * `$t`: the (common) type of the static variables.
 * `$ids_data`: The list of type `&[(I, $t)]` where $t is as above, and I is a type implementing Display,
 though most commonly String or &'static str. This is a list of identifiers for the variables paired with
-their values.
+their values. Any iterable of `(I, $t)` pairs, or references to them, is also accepted, so entries may be
+generated lazily with an iterator adaptor.
+
+Alternatively, pass `mixed` in place of the visibility, followed by a list of type `&[(bool, I, $t)]`. As with
+`write_struct!`, the first component indicates the visibility of each variable (true = public, false = private).
 
 ## Notes
 * Intended for stack-allocated data. For heap-allocated data, use `write_fns` instead.
@@ -781,10 +2608,16 @@ It is anticipated that this will be more convenient in the typical use cases of
 #[macro_export]
 macro_rules! write_statics {
     (public, $id_group:ident, $t:ty, $ids_data:expr) => {
-        rustifact::__write_internal!(static, $id_group, $t, true, $ids_data);
+        rustifact::__write_internal!(static, $id_group, $t, public, $ids_data);
+    };
+    (pub_crate, $id_group:ident, $t:ty, $ids_data:expr) => {
+        rustifact::__write_internal!(static, $id_group, $t, pub_crate, $ids_data);
     };
     (private, $id_group:ident, $t:ty, $ids_data:expr) => {
-        rustifact::__write_internal!(static, $id_group, $t, false, $ids_data);
+        rustifact::__write_internal!(static, $id_group, $t, private, $ids_data);
+    };
+    (mixed, $id_group:ident, $t:ty, $vis_ids_data:expr) => {
+        rustifact::__write_internal!(static, $id_group, $t, $vis_ids_data);
     };
 }
 
@@ -793,12 +2626,14 @@ macro_rules! write_statics {
 Makes the constants available for import into the main crate via `use_symbols`.
 
 ## Parameters
-* `public` or `private`: whether to make the constants publicly visible after import with `use_symbols`.
+* `public`, `pub_crate` or `private`: the visibility (`pub`, `pub(crate)` or none) of the constants after import
+with `use_symbols`.
 * `$id_group`: the group alias by which these variables are referred when importing with `use_symbols`.
 * `$t`: the (common) type of the static variables.
 * `$ids_data`: The list of type `&[(I, $t)]` where $t is as above, and I is a type implementing Display,
 though most commonly String or &'static str. This is a list of identifiers for the constants paired with
-their values.
+their values. As with `write_statics!`, any iterable of `(I, $t)` pairs, or references to them, is also
+accepted.
 
 ## Notes
 * Intended for stack-allocated data. For heap-allocated data, use `write_fns` instead.
@@ -807,10 +2642,13 @@ It is anticipated that this will be more convenient in the typical use cases of
 #[macro_export]
 macro_rules! write_consts {
     (public, $id_group:ident, $t:ty, $ids_data:expr) => {
-        rustifact::__write_internal!(const, $id_group, $t, true, $ids_data);
+        rustifact::__write_internal!(const, $id_group, $t, public, $ids_data);
+    };
+    (pub_crate, $id_group:ident, $t:ty, $ids_data:expr) => {
+        rustifact::__write_internal!(const, $id_group, $t, pub_crate, $ids_data);
     };
     (private, $id_group:ident, $t:ty, $ids_data:expr) => {
-        rustifact::__write_internal!(const, $id_group, $t, false, $ids_data);
+        rustifact::__write_internal!(const, $id_group, $t, private, $ids_data);
     };
 }
 
@@ -819,12 +2657,14 @@ macro_rules! write_consts {
 Makes the getter functions available for import into the main crate via `use_symbols`.
 
 ## Parameters
-* `public` or `private`: whether to make the functions publicly visible after import with `use_symbols`.
+* `public`, `pub_crate` or `private`: the visibility (`pub`, `pub(crate)` or none) of the functions after import
+with `use_symbols`.
 * `$id_group`: the group alias by which these functions are referred when importing with `use_symbols`.
 * `$t`: the (common) return type of the getter functions.
 * `$ids_data`: The list of type `&[(I, $t)]` where $t is as above, and I is a type implementing Display,
 though most commonly String or &'static str. This is a list of identifiers for the functions paired with
-their values.
+their values. Any iterable of `(I, $t)` pairs, or references to them, is also accepted, so entries may be
+generated lazily with an iterator adaptor.
 
 ## Notes
 * Intended for heap-allocated data. For stack-allocated data, consider `write_consts` or `write_static` instead.
@@ -833,10 +2673,13 @@ It is anticipated that this will be more convenient in the typical use cases of
 #[macro_export]
 macro_rules! write_fns {
     (public, $id_group:ident, $t:ty, $ids_data:expr) => {
-        rustifact::__write_internal_fns!($id_group, $t, true, $ids_data);
+        rustifact::__write_internal_fns!($id_group, $t, public, $ids_data);
+    };
+    (pub_crate, $id_group:ident, $t:ty, $ids_data:expr) => {
+        rustifact::__write_internal_fns!($id_group, $t, pub_crate, $ids_data);
     };
     (private, $id_group:ident, $t:ty, $ids_data:expr) => {
-        rustifact::__write_internal_fns!($id_group, $t, false, $ids_data);
+        rustifact::__write_internal_fns!($id_group, $t, private, $ids_data);
     };
 }
 
@@ -845,12 +2688,17 @@ macro_rules! write_fns {
 Makes the `struct` type available for import into the main crate via `use_symbols`.
 
 ## Parameters
-* `public` or `private`: whether to make the struct publicly visible after import with `use_symbols`.
+* `public`, `pub_crate` or `private`: the visibility (`pub`, `pub(crate)` or none) of the struct after import
+with `use_symbols`.
 * `$id`: the name of the struct type, and the identifier by which it is referred when importing with
 `use_symbols`.
 * `$vis_ids_types`: The list of type `&[(bool, I, T)]` where the first component indicates visibility
 (true = public, false = private) of a field, I is the field's identifier having type String or &str, and T
 is the field's type: also having type String or &str.
+* `$derives` (optional): a list of traits to derive for the struct, such as `Debug` or `Clone`. Has type
+`&[S]` where S is String or &str.
+* `$repr` (optional, following `$derives`): the representation of the struct, emitted as a `#[repr(...)]`
+attribute, such as `\"C\"` or `\"C, align(8)\"`. Has type String or &str.
 
 ## Notes
 Before using `write_struct!` carefully consider all other approaches. Defining a struct in the usual way
@@ -893,11 +2741,147 @@ rustifact::use_symbols!(Foo);
 ```"]
 #[macro_export]
 macro_rules! write_struct {
-    (public, $id_struct:ident, $vis_ids_types:expr) => {
-        rustifact::__write_internal_struct!($id_struct, true, $vis_ids_types);
+    (public, $id_struct:ident, $vis_ids_types:expr $(, $derives:expr $(, $repr:expr)?)?) => {
+        rustifact::__write_internal_struct!(
+            $id_struct,
+            public,
+            $vis_ids_types
+            $(, rustifact::internal::parse_derives($derives)
+            $(, rustifact::internal::parse_repr($repr))?)?
+        );
+    };
+    (pub_crate, $id_struct:ident, $vis_ids_types:expr $(, $derives:expr $(, $repr:expr)?)?) => {
+        rustifact::__write_internal_struct!(
+            $id_struct,
+            pub_crate,
+            $vis_ids_types
+            $(, rustifact::internal::parse_derives($derives)
+            $(, rustifact::internal::parse_repr($repr))?)?
+        );
+    };
+    (private, $id_struct:ident, $vis_ids_types:expr $(, $derives:expr $(, $repr:expr)?)?) => {
+        rustifact::__write_internal_struct!(
+            $id_struct,
+            private,
+            $vis_ids_types
+            $(, rustifact::internal::parse_derives($derives)
+            $(, rustifact::internal::parse_repr($repr))?)?
+        );
+    };
+}
+
+#[doc = "Write an enum type definition.
+
+Makes the `enum` type available for import into the main crate via `use_symbols`.
+
+## Parameters
+* `public` or `private`: whether to make the enum publicly visible after import with `use_symbols`.
+* `$id_enum`: the name of the enum type, and the identifier by which it is referred when importing with
+`use_symbols`.
+* `$variants`: The list of type `&[(I, Option<FieldSpec<S>>)]` where I is the variant's identifier having type
+String or &str, and the second component specifies the variant's fields: `None` for a unit variant, or a
+[`FieldSpec`] for a tuple or struct variant, or a unit variant with an explicit discriminant. Field names,
+types and discriminants S have type String or &str.
+* `$repr` (optional): the representation of the enum, emitted as a `#[repr(...)]` attribute, such as
+`\"u8\"` or `\"C\"`. Has type String or &str.
+
+## Notes
+Before using `write_enum!` carefully consider all other approaches. Defining an enum in the usual way
+should be preferred when this is possible.
+
+## Example
+build.rs
+ ```no_run
+use rustifact::FieldSpec;
+
+fn main() {
+    let feature_variants = vec![
+        (\"Basic\", None),
+        (\"Sized\", Some(FieldSpec::Tuple(vec![\"usize\"]))),
+        (\"Named\", Some(FieldSpec::Struct(vec![(\"name\", \"&'static str\")]))),
+    ];
+    rustifact::write_enum!(public, Feature, &feature_variants);
+    let level_variants = vec![
+        (\"Low\", Some(FieldSpec::Discriminant(\"1\"))),
+        (\"High\", Some(FieldSpec::Discriminant(\"10\"))),
+    ];
+    rustifact::write_enum!(public, Level, &level_variants);
+}
+```
+
+src/main.rs
+```no_run
+rustifact::use_symbols!(Feature, Level);
+// The above line is equivalent to the declarations:
+// pub enum Feature {
+//     Basic,
+//     Sized(usize),
+//     Named { name: &'static str },
+// }
+// pub enum Level {
+//     Low = 1,
+//     High = 10,
+// }
+```"]
+#[macro_export]
+macro_rules! write_enum {
+    (public, $id_enum:ident, $variants:expr $(, $repr:expr)?) => {
+        rustifact::__write_internal_enum!(
+            $id_enum,
+            true,
+            $variants
+            $(, rustifact::internal::parse_repr($repr))?
+        );
+    };
+    (private, $id_enum:ident, $variants:expr $(, $repr:expr)?) => {
+        rustifact::__write_internal_enum!(
+            $id_enum,
+            false,
+            $variants
+            $(, rustifact::internal::parse_repr($repr))?
+        );
+    };
+}
+
+#[doc = "Write a type alias.
+
+Makes the type alias available for import into the main crate via `use_symbols`.
+
+## Parameters
+* `public` or `private`: whether to make the type alias publicly visible after import with `use_symbols`.
+* `$id_alias`: the name of the type alias, and the identifier by which it is referred when importing with
+`use_symbols`.
+* `$type_str`: the aliased type, having type String or &str.
+
+## Example
+build.rs
+ ```no_run
+fn index_type() -> &'static str {
+    if std::env::var(\"CARGO_CFG_TARGET_POINTER_WIDTH\").unwrap() == \"64\" {
+        \"u64\"
+    } else {
+        \"u32\"
+    }
+}
+
+fn main() {
+    rustifact::write_type_alias!(public, Index, index_type());
+}
+```
+
+src/main.rs
+```no_run
+rustifact::use_symbols!(Index);
+// The above line is equivalent to the declaration (on 64-bit targets):
+// pub type Index = u64;
+```"]
+#[macro_export]
+macro_rules! write_type_alias {
+    (public, $id_alias:ident, $type_str:expr) => {
+        rustifact::__write_internal_type_alias!($id_alias, true, $type_str);
     };
-    (private, $id_struct:ident, $vis_ids_types:expr) => {
-        rustifact::__write_internal_struct!($id_struct, false, $vis_ids_types);
+    (private, $id_alias:ident, $type_str:expr) => {
+        rustifact::__write_internal_type_alias!($id_alias, false, $type_str);
     };
 }
 
@@ -912,6 +2896,8 @@ Makes the `struct` type available for import into the main crate via `use_symbol
 * `$t`: the type of *all* fields of this struct
 * `$vis_ids`: The list of type `&[(bool, I)]` where the first component indicates visibility
 (true = public, false = private) of a field, and I is the field's identifier having type String or &str.
+* `$derives` (optional): a list of traits to derive for the struct, such as `Debug` or `Clone`. Has type
+`&[S]` where S is String or &str.
 
 ## Notes
 Before using `write_struct_uniform!` carefully consider all other approaches.
@@ -958,6 +2944,24 @@ macro_rules! write_struct_uniform {
     (private, $id_struct:ident, $t:ty, $vis_ids_types:expr) => {
         rustifact::__write_internal_struct_uniform!($id_struct, $t, false, $vis_ids_types);
     };
+    (public, $id_struct:ident, $t:ty, $vis_ids_types:expr, $derives:expr) => {
+        rustifact::__write_internal_struct_uniform!(
+            $id_struct,
+            $t,
+            true,
+            $vis_ids_types,
+            rustifact::internal::parse_derives($derives)
+        );
+    };
+    (private, $id_struct:ident, $t:ty, $vis_ids_types:expr, $derives:expr) => {
+        rustifact::__write_internal_struct_uniform!(
+            $id_struct,
+            $t,
+            false,
+            $vis_ids_types,
+            rustifact::internal::parse_derives($derives)
+        );
+    };
 }
 
 #[doc = "Write a struct initialisation expression.
@@ -972,6 +2976,15 @@ the second parameter to `init_symbols!`.
 * `$t`: the type of *all* fields of this struct
 * `$ids_vals`: The list of type `&[(I, V)]` where I is the field's identifier having type String or &str,
 and V is the value (of type $t) to assign to the field.
+* `const` (optional): panic unless every value can be constructed in a const context.
+
+Panics if the identifiers don't match the fields of a struct written by `write_struct_uniform!`, naming
+any unknown or uninitialised field.
+
+The initialisation expression may be used wherever an expression of the struct's type is expected, including
+the value of a `const` or `static`. Values that are heap-allocated (such as a `Vec`) can't be constructed in
+these contexts, so pass `const` to have this checked when the build script runs, rather than when the main
+crate is compiled.
 
 ## Notes
 Before using `write_struct_uniform!` carefully consider all other approaches.
@@ -1000,7 +3013,7 @@ fn main() {
         (\"field_c\", (2u32, \"ghi\")),
     ];
     rustifact::write_struct_uniform!(public, Foo, (u32, &'static str), &foo_fields);
-    rustifact::write_struct_uniform_init!(Foo, Init, (u32, &'static str), &foo_vals);
+    rustifact::write_struct_uniform_init!(Foo, Init, (u32, &'static str), &foo_vals, const);
 }
 ```
 
@@ -1018,10 +3031,13 @@ static FOO_INIT: Foo = rustifact::init_symbols!(Foo, Init);
 //     field_c: (2, \"ghi\"),
 // }
 
+const FOO_CONST: Foo = rustifact::init_symbols!(Foo, Init);
+
 fn main() {
     assert!(FOO_INIT.field_a == (0, \"abc\"));
     assert!(FOO_INIT.field_b == (1, \"def\"));
     assert!(FOO_INIT.field_c == (2, \"ghi\"));
+    assert!(FOO_CONST.field_b == (1, \"def\"));
 }
 ```"]
 #[macro_export]
@@ -1029,4 +3045,149 @@ macro_rules! write_struct_uniform_init {
     ($id_struct:ident, $id_vals:ident, $t:ty, $ids_vals:expr) => {
         rustifact::__write_internal_struct_uniform_init!($id_struct, $id_vals, $t, $ids_vals);
     };
+    ($id_struct:ident, $id_vals:ident, $t:ty, $ids_vals:expr, const) => {
+        let ids_vals = $ids_vals;
+        for (_, val) in ids_vals.iter() {
+            rustifact::internal::check_const_safe(
+                concat!(stringify!($id_struct), "_", stringify!($id_vals)),
+                val,
+            );
+        }
+        rustifact::__write_internal_struct_uniform_init!($id_struct, $id_vals, $t, ids_vals);
+    };
+}
+
+#[doc = "Write an impl block of methods for a type.
+
+Makes the impl block available for import into the main crate via `impl_symbols`. Combined with
+`write_struct!`, this allows generation of complete wrapper APIs.
+
+## Parameters
+* `$id_type`: the name of the type, and the identifier by which the impl block is referred when importing
+with `impl_symbols`.
+* `$sigs_bodies`: The list of type `&[(S, B)]` where S is a method's signature (for example,
+`pub fn len(&self) -> usize`) and B is the method's body, without enclosing braces. Both have type String
+or &str.
+
+## Example
+build.rs
+ ```no_run
+fn main() {
+    let fields = vec![(false, \"items\", \"Vec<u32>\")];
+    rustifact::write_struct!(private, Stack, &fields);
+    let methods = vec![
+        (\"fn new() -> Stack\", \"Stack { items: Vec::new() }\"),
+        (\"fn depth(&self) -> usize\", \"self.items.len()\"),
+    ];
+    rustifact::write_impl!(Stack, &methods);
+}
+```
+
+src/main.rs
+```no_run
+rustifact::use_symbols!(Stack);
+rustifact::impl_symbols!(Stack);
+// The above lines are equivalent to the declarations:
+// struct Stack {
+//     items: Vec<u32>,
+// }
+// impl Stack {
+//     fn new() -> Stack { Stack { items: Vec::new() } }
+//     fn depth(&self) -> usize { self.items.len() }
+// }
+
+fn main() {
+    assert!(Stack::new().depth() == 0);
+}
+```"]
+#[macro_export]
+macro_rules! write_impl {
+    ($id_type:ident, $sigs_bodies:expr) => {
+        rustifact::__write_internal_impl!($id_type, $sigs_bodies);
+    };
+}
+
+#[doc = "Write arbitrary Rust source code.
+
+Makes the items available for import into the main crate via `use_symbols`. This is an escape hatch for
+code that the other `write_`... macros can't produce, such as `use` declarations or macro invocations.
+
+## Parameters
+* `$id`: the identifier by which the items are referred when importing with `use_symbols`.
+* `$source`: the source code of a sequence of items, having type String or &str. Panics if it can't be
+parsed as a Rust source file.
+
+## Example
+build.rs
+ ```no_run
+let source = \"
+    use std::collections::HashMap;
+    fn empty_index() -> HashMap<&'static str, usize> { HashMap::new() }
+\";
+rustifact::write_raw!(INDEX, source);
+```
+
+src/main.rs
+```no_run
+rustifact::use_symbols!(INDEX);
+// The above line is equivalent to the declarations:
+// use std::collections::HashMap;
+// fn empty_index() -> HashMap<&'static str, usize> { HashMap::new() }
+```"]
+#[macro_export]
+macro_rules! write_raw {
+    ($id:ident, $source:expr) => {
+        let file = rustifact::internal::parse_source(stringify!($id), &$source);
+        let tokens = rustifact::internal::quote! { #file };
+        rustifact::__write_tokens_with_internal!($id, private, tokens);
+    };
+}
+
+#[doc = "Write several symbols concurrently.
+
+Runs each of the given `write_`... macro calls on its own thread. Converting data to tokens, and
+particularly pretty-printing the generated code, is CPU intensive, so build scripts exporting many large
+symbols may complete considerably faster. Each symbol is written to its own file, as usual.
+
+## Parameters
+A sequence of `write_`... macro calls (without the `rustifact::` prefix), each followed by a semicolon.
+
+## Notes
+* Data referenced by the macro calls is shared between threads, so must be `Sync`.
+* If any of the macro calls panics, `write_all!` panics once all threads have finished.
+
+## Example
+build.rs
+ ```no_run
+use rustifact::ToTokenStream;
+
+fn powers(exp: u32) -> Vec<u64> {
+    (0..100_000u64).map(|i| i.pow(exp)).collect()
+}
+
+fn main() {
+    let squares = powers(2);
+    let cubes = powers(3);
+    rustifact::write_all! {
+        write_static_array!(SQUARES, u64, &squares[..]);
+        write_static_array!(CUBES, u64, &cubes[..]);
+    }
+}
+```
+
+src/main.rs
+```no_run
+rustifact::use_symbols!(SQUARES, CUBES);
+```"]
+#[macro_export]
+macro_rules! write_all {
+    ($($write:ident ! ($($args:tt)*));* $(;)?) => {
+        std::thread::scope(|scope| {
+            $(
+                scope.spawn(|| {
+                    rustifact::$write!($($args)*);
+                });
+            )*
+        });
+    };
 }
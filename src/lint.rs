@@ -0,0 +1,153 @@
+use quote::ToTokens;
+use std::sync::Mutex;
+use syn::{Attribute, Item};
+
+/// The lints allowed on generated code, unless set otherwise via [`set_allowed_lints`].
+pub const ALLOWED_LINTS_DEFAULT: &[&str] = &["clippy::all", "dead_code"];
+
+static ALLOWED: Mutex<Option<Vec<String>>> = Mutex::new(None);
+
+/// Set the lints allowed on the symbols subsequently written by the build script.
+///
+/// Each symbol is written with an `#[allow(...)]` attribute for these lints, so that generated code
+/// (large arrays, unused constants, and so on) doesn't pollute the lint output of the main crate.
+/// Defaults to [`ALLOWED_LINTS_DEFAULT`]. Pass an empty list to write symbols without the attribute.
+///
+/// Panics if any of `lints` isn't a valid lint path, such as `dead_code` or `clippy::large_const_arrays`.
+///
+/// ## Further notes
+/// * Must be called from a build script (build.rs) only.
+///
+/// ## Example
+/// build.rs
+/// ```no_run
+/// use rustifact::ToTokenStream;
+///
+/// fn main() {
+///     rustifact::set_allowed_lints(&["clippy::large_const_arrays"]);
+///     rustifact::write_const!(ZEROES, [u64; 10000], &[0u64; 10000]);
+/// }
+/// ```
+pub fn set_allowed_lints<I, S>(lints: I)
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let lints = lints
+        .into_iter()
+        .map(|lint| {
+            let lint = lint.as_ref();
+            if syn::parse_str::<syn::Path>(lint).is_err() {
+                panic!(
+                    "Couldn't allow the lint '{}': it isn't a valid lint path",
+                    lint
+                );
+            }
+            lint.to_string()
+        })
+        .collect();
+    *ALLOWED.lock().unwrap() = Some(lints);
+}
+
+fn allow_attribute() -> Option<Attribute> {
+    let allowed = ALLOWED.lock().unwrap();
+    let lints: Vec<syn::Path> = match &*allowed {
+        Some(lints) => lints.iter().map(|l| syn::parse_str(l).unwrap()).collect(),
+        None => ALLOWED_LINTS_DEFAULT
+            .iter()
+            .map(|l| syn::parse_str(l).unwrap())
+            .collect(),
+    };
+    if lints.is_empty() {
+        return None;
+    }
+    Some(syn::parse_quote! { #[allow(#(#lints),*)] })
+}
+
+fn item_attrs(item: &mut Item) -> Option<&mut Vec<Attribute>> {
+    match item {
+        Item::Const(i) => Some(&mut i.attrs),
+        Item::Enum(i) => Some(&mut i.attrs),
+        Item::ExternCrate(i) => Some(&mut i.attrs),
+        Item::Fn(i) => Some(&mut i.attrs),
+        Item::ForeignMod(i) => Some(&mut i.attrs),
+        Item::Impl(i) => Some(&mut i.attrs),
+        Item::Macro(i) => Some(&mut i.attrs),
+        Item::Mod(i) => Some(&mut i.attrs),
+        Item::Static(i) => Some(&mut i.attrs),
+        Item::Struct(i) => Some(&mut i.attrs),
+        Item::Trait(i) => Some(&mut i.attrs),
+        Item::TraitAlias(i) => Some(&mut i.attrs),
+        Item::Type(i) => Some(&mut i.attrs),
+        Item::Union(i) => Some(&mut i.attrs),
+        Item::Use(i) => Some(&mut i.attrs),
+        _ => None,
+    }
+}
+
+/// Place the allowed lints on each item of the generated file, unless they're already there.
+pub fn allow_lints(file: &mut syn::File) {
+    let Some(attr) = allow_attribute() else {
+        return;
+    };
+    let attr_str = attr.to_token_stream().to_string();
+    for item in file.items.iter_mut() {
+        if let Some(attrs) = item_attrs(item) {
+            if !attrs
+                .iter()
+                .any(|a| a.to_token_stream().to_string() == attr_str)
+            {
+                attrs.insert(0, syn::parse_quote! { #attr });
+            }
+        }
+    }
+}
+
+/// Make each item of the generated file public.
+pub fn make_public(file: &mut syn::File) {
+    let vis = || syn::Visibility::Public(Default::default());
+    for item in file.items.iter_mut() {
+        match item {
+            Item::Const(i) => i.vis = vis(),
+            Item::Enum(i) => i.vis = vis(),
+            Item::ExternCrate(i) => i.vis = vis(),
+            Item::Fn(i) => i.vis = vis(),
+            Item::Mod(i) => i.vis = vis(),
+            Item::Static(i) => i.vis = vis(),
+            Item::Struct(i) => i.vis = vis(),
+            Item::Trait(i) => i.vis = vis(),
+            Item::TraitAlias(i) => i.vis = vis(),
+            Item::Type(i) => i.vis = vis(),
+            Item::Union(i) => i.vis = vis(),
+            Item::Use(i) => i.vis = vis(),
+            _ => {}
+        }
+    }
+}
+
+/// Make each private item of the generated file visible to the parent module. Used for symbols imported
+/// under an alias, which are included into a hidden module.
+pub fn make_parent_visible(file: &mut syn::File) {
+    fn raise(vis: &mut syn::Visibility) {
+        if let syn::Visibility::Inherited = vis {
+            *vis = syn::parse_quote! { pub(super) };
+        }
+    }
+    for item in file.items.iter_mut() {
+        match item {
+            Item::Const(i) => raise(&mut i.vis),
+            Item::Enum(i) => raise(&mut i.vis),
+            Item::ExternCrate(i) => raise(&mut i.vis),
+            Item::Fn(i) => raise(&mut i.vis),
+            Item::Mod(i) => raise(&mut i.vis),
+            Item::Static(i) => raise(&mut i.vis),
+            Item::Struct(i) => raise(&mut i.vis),
+            Item::Trait(i) => raise(&mut i.vis),
+            Item::TraitAlias(i) => raise(&mut i.vis),
+            Item::Type(i) => raise(&mut i.vis),
+            Item::Union(i) => raise(&mut i.vis),
+            Item::Use(i) => raise(&mut i.vis),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::{Mutex, PoisonError};
+
+// The names of the files written (outside of any namespace) by this run of the build script.
+static WRITTEN: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+fn manifest_path() -> String {
+    format!(
+        "{}/rustifact_{}.manifest.rs",
+        crate::namespace::generated_dir(),
+        std::env::var("CARGO_PKG_NAME").unwrap(),
+    )
+}
+
+// The name of the file written to `path`, if it's outside of any namespace.
+fn file_name(path: &Path) -> Option<String> {
+    let generated_dir = crate::namespace::generated_dir();
+    let file_name = path.strip_prefix(&generated_dir).ok()?.to_string_lossy();
+    if file_name.contains(['/', '\\']) {
+        return None;
+    }
+    Some(file_name.into_owned())
+}
+
+/// Clear the manifest left by any previous run of the build script.
+pub fn reset_manifest() {
+    *WRITTEN.lock().unwrap_or_else(PoisonError::into_inner) = Some(HashSet::new());
+    crate::transaction::write_file(manifest_path(), "").unwrap();
+}
+
+/// Record the symbol written to `path_str` in the manifest, for import by `use_all_symbols!`.
+///
+/// Each symbol is appended to the manifest as it's recorded. Symbols written in a namespace aren't
+/// recorded, as they may share names.
+pub fn record_symbol(path_str: &str) {
+    let Some(file_name) = file_name(Path::new(path_str)) else {
+        return;
+    };
+    let mut written = WRITTEN.lock().unwrap_or_else(PoisonError::into_inner);
+    if written
+        .get_or_insert_with(HashSet::new)
+        .insert(file_name.clone())
+    {
+        let line = format!(
+            "include!(concat!(env!(\"OUT_DIR\"), \"/rustifact/{}\"));\n",
+            file_name
+        );
+        crate::transaction::append_file(manifest_path(), line).unwrap();
+    }
+}
+
+/// Reload the recorded symbols from the manifest, as writes to it were rolled back by `transaction`.
+pub fn reload_symbols() {
+    let manifest = std::fs::read_to_string(manifest_path()).unwrap_or_default();
+    let recorded = manifest
+        .lines()
+        .filter_map(|line| line.split("/rustifact/").nth(1)?.split('"').next())
+        .map(str::to_string)
+        .collect();
+    *WRITTEN.lock().unwrap_or_else(PoisonError::into_inner) = Some(recorded);
+}
@@ -0,0 +1,91 @@
+use std::sync::Mutex;
+
+static NAMESPACE: Mutex<Option<String>> = Mutex::new(None);
+
+/// Place the symbols subsequently written by the build script in the namespace `ns`.
+///
+/// Symbols in different namespaces may share a name, so unrelated parts of a build script needn't
+/// coordinate their choice of symbol names. Symbols in a namespace are imported into the main crate
+/// with `use_symbols!(in ns: ...)`.
+///
+/// The namespace remains in effect until the next call to `set_namespace` or [`clear_namespace`].
+///
+/// Panics if `ns` isn't a valid identifier.
+///
+/// ## Further notes
+/// * Must be called from a build script (build.rs) only.
+///
+/// ## Example
+/// build.rs
+/// ```no_run
+/// use rustifact::ToTokenStream;
+///
+/// fn main() {
+///     rustifact::set_namespace("audio");
+///     rustifact::write_const!(RATE, u32, &44100u32);
+///     rustifact::set_namespace("video");
+///     rustifact::write_const!(RATE, u32, &60u32);
+///     rustifact::clear_namespace();
+/// }
+/// ```
+///
+/// src/main.rs
+/// ```no_run
+/// mod audio {
+///     rustifact::use_symbols!(in audio: RATE);
+///     pub fn rate() -> u32 {
+///         RATE
+///     }
+/// }
+///
+/// mod video {
+///     rustifact::use_symbols!(in video: RATE);
+///     pub fn rate() -> u32 {
+///         RATE
+///     }
+/// }
+/// ```
+pub fn set_namespace(ns: &str) {
+    if syn::parse_str::<syn::Ident>(ns).is_err() {
+        panic!(
+            "Couldn't set the namespace '{}': it isn't a valid identifier",
+            ns
+        );
+    }
+    *NAMESPACE.lock().unwrap() = Some(ns.to_string());
+}
+
+/// Place the symbols subsequently written by the build script outside of any namespace.
+///
+/// This undoes [`set_namespace`].
+///
+/// ## Further notes
+/// * Must be called from a build script (build.rs) only.
+pub fn clear_namespace() {
+    *NAMESPACE.lock().unwrap() = None;
+}
+
+/// The directory, under `OUT_DIR`, holding the files written by the build script.
+///
+/// Keeping these files apart from any others in `OUT_DIR` makes them easier to inspect. The directory is
+/// created if necessary.
+pub fn generated_dir() -> String {
+    let dir = format!("{}/rustifact", std::env::var("OUT_DIR").unwrap());
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// The path, relative to `generated_dir()`, of the directory holding the files of the current namespace.
+///
+/// This is empty outside of any namespace, and otherwise ends with `/`. The directory is created if
+/// necessary.
+pub fn namespace_dir() -> String {
+    match &*NAMESPACE.lock().unwrap() {
+        Some(ns) => {
+            let dir = format!("{}/{}", generated_dir(), ns);
+            std::fs::create_dir_all(dir).unwrap();
+            format!("{}/", ns)
+        }
+        None => String::new(),
+    }
+}
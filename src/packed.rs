@@ -0,0 +1,37 @@
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// An element type which may be exported in packed (binary) form by `write_static_array_packed!`.
+///
+/// Implemented for the fixed-size primitive numeric types, for which any bit pattern is valid. The trait
+/// is sealed, as packed arrays are reinterpreted from their bytes.
+pub trait PackedElement: Copy + sealed::Sealed {
+    /// The size of an element, in bytes.
+    const SIZE: usize;
+
+    /// Append the little-endian bytes of this element to `out`.
+    fn write_le_bytes(&self, out: &mut Vec<u8>);
+}
+
+macro_rules! packed_element {
+    ($($t:ty)*) => {
+        $(
+            impl sealed::Sealed for $t {}
+
+            impl PackedElement for $t {
+                const SIZE: usize = std::mem::size_of::<$t>();
+
+                fn write_le_bytes(&self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_le_bytes());
+                }
+            }
+        )*
+    };
+}
+
+packed_element! {
+    i8 i16 i32 i64 i128
+    u8 u16 u32 u64 u128
+    f32 f64
+}
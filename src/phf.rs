@@ -3,6 +3,11 @@ mod map;
 #[cfg(feature = "map")]
 pub use map::{Map, MapBuilder};
 
+#[cfg(feature = "map")]
+mod case_insensitive_map;
+#[cfg(feature = "map")]
+pub use case_insensitive_map::{CaseInsensitiveMap, CaseInsensitiveMapBuilder};
+
 #[cfg(feature = "map")]
 mod ordered_map;
 #[cfg(feature = "map")]
@@ -17,3 +22,36 @@ pub use set::{Set, SetBuilder};
 mod ordered_set;
 #[cfg(feature = "set")]
 pub use ordered_set::{OrderedSet, OrderedSetBuilder};
+
+/// The error returned when a key is added to a map or set builder more than once.
+///
+/// *This API requires one of the following crate features to be activated: `map`, `set`*
+#[cfg(any(feature = "map", feature = "set"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateKeyError {
+    key: String,
+}
+
+#[cfg(any(feature = "map", feature = "set"))]
+impl DuplicateKeyError {
+    pub(crate) fn new<K: crate::ToTokenStream + ?Sized>(key: &K) -> DuplicateKeyError {
+        DuplicateKeyError {
+            key: key.to_tok_stream().to_string(),
+        }
+    }
+
+    /// The duplicated key, as it would appear in the generated code.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+}
+
+#[cfg(any(feature = "map", feature = "set"))]
+impl std::fmt::Display for DuplicateKeyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "duplicate key `{}`", self.key)
+    }
+}
+
+#[cfg(any(feature = "map", feature = "set"))]
+impl std::error::Error for DuplicateKeyError {}
@@ -0,0 +1,185 @@
+use super::{DuplicateKeyError, Map, MapBuilder};
+use crate::tokens::ToTokenStream;
+use proc_macro2::TokenStream;
+use quote::quote;
+use std::borrow::Cow;
+
+/// A compile time builder for an immutable map with ASCII case-insensitive string keys.
+///
+/// Produces a highly optimised `CaseInsensitiveMap` when output (for example, by `write_static!`) from the
+/// build script. Keys are converted to ASCII lowercase as they're added, so `"Content-Type"` and
+/// `"content-type"` are the same key.
+///
+/// *This API requires the following crate feature to be activated: `map`*
+pub struct CaseInsensitiveMapBuilder<V>(MapBuilder<String, V>);
+
+/// An immutable map with ASCII case-insensitive string keys, and lookup via a perfect hash function.
+///
+/// Constructable at compile time with a `CaseInsensitiveMapBuilder`. No iteration order is specified.
+/// Keys are stored in ASCII lowercase, and query keys are converted to lowercase before lookup.
+///
+/// *This API requires the following crate feature to be activated: `map`*
+pub struct CaseInsensitiveMap<V: 'static>(Map<&'static str, V>);
+
+impl<V> CaseInsensitiveMapBuilder<V>
+where
+    V: ToTokenStream,
+{
+    pub fn new() -> CaseInsensitiveMapBuilder<V> {
+        CaseInsensitiveMapBuilder(MapBuilder::new())
+    }
+
+    /// As with `new`, but the generated code refers to the `phf` crate via `path`, rather than
+    /// `rustifact::internal::phf`. Useful when `rustifact` is re-exported under another path.
+    pub fn with_phf_path(path: &str) -> CaseInsensitiveMapBuilder<V> {
+        CaseInsensitiveMapBuilder(MapBuilder::with_phf_path(path))
+    }
+
+    /// Add an entry to the map.
+    ///
+    /// Panics if `key` (ignoring ASCII case) has already been added.
+    #[inline]
+    pub fn entry<S: AsRef<str>>(&mut self, key: S, value: V) {
+        if let Err(e) = self.try_entry(key, value) {
+            panic!(
+                "Couldn't add an entry to the CaseInsensitiveMapBuilder: {}",
+                e
+            );
+        }
+    }
+
+    /// Add an entry to the map, returning an error if `key` (ignoring ASCII case) has already been added.
+    #[inline]
+    pub fn try_entry<S: AsRef<str>>(&mut self, key: S, value: V) -> Result<(), DuplicateKeyError> {
+        self.0.try_entry(key.as_ref().to_ascii_lowercase(), value)
+    }
+}
+
+impl<V> Default for CaseInsensitiveMapBuilder<V>
+where
+    V: ToTokenStream,
+{
+    fn default() -> CaseInsensitiveMapBuilder<V> {
+        CaseInsensitiveMapBuilder::new()
+    }
+}
+
+impl<V> CaseInsensitiveMap<V> {
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    #[inline]
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.0.contains_key(&*lowercase(key))
+    }
+
+    #[inline]
+    pub fn get(&self, key: &str) -> Option<&V> {
+        self.0.get(&*lowercase(key))
+    }
+
+    #[inline]
+    pub fn get_entry(&self, key: &str) -> Option<(&&'static str, &V)> {
+        self.0.get_entry(&*lowercase(key))
+    }
+
+    #[inline]
+    pub fn entries(&self) -> phf::map::Entries<'_, &'static str, V> {
+        self.0.entries()
+    }
+
+    #[inline]
+    pub fn keys(&self) -> phf::map::Keys<'_, &'static str, V> {
+        self.0.keys()
+    }
+
+    #[inline]
+    pub fn values(&self) -> phf::map::Values<'_, &'static str, V> {
+        self.0.values()
+    }
+
+    /// An implementation detail. You shouldn't need to call this function.
+    #[inline]
+    pub const fn init_raw(map: Map<&'static str, V>) -> CaseInsensitiveMap<V> {
+        CaseInsensitiveMap(map)
+    }
+}
+
+/// Lowercase `key`, avoiding allocation when it's already lowercase.
+fn lowercase(key: &str) -> Cow<'_, str> {
+    if key.bytes().any(|b| b.is_ascii_uppercase()) {
+        Cow::Owned(key.to_ascii_lowercase())
+    } else {
+        Cow::Borrowed(key)
+    }
+}
+
+impl<V> std::fmt::Debug for CaseInsensitiveMap<V>
+where
+    V: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<V> PartialEq for CaseInsensitiveMap<V>
+where
+    V: PartialEq,
+{
+    fn eq(&self, other: &CaseInsensitiveMap<V>) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<V> Eq for CaseInsensitiveMap<V> where V: Eq {}
+
+impl<'a, V> IntoIterator for &'a CaseInsensitiveMap<V> {
+    type Item = (&'a &'static str, &'a V);
+    type IntoIter = phf::map::Entries<'a, &'static str, V>;
+
+    #[inline]
+    fn into_iter(self) -> phf::map::Entries<'a, &'static str, V> {
+        self.entries()
+    }
+}
+
+impl<S, V> FromIterator<(S, V)> for CaseInsensitiveMapBuilder<V>
+where
+    S: AsRef<str>,
+    V: ToTokenStream,
+{
+    /// Panics if any key (ignoring ASCII case) occurs more than once.
+    fn from_iter<I: IntoIterator<Item = (S, V)>>(iter: I) -> CaseInsensitiveMapBuilder<V> {
+        let mut builder = CaseInsensitiveMapBuilder::new();
+        builder.extend(iter);
+        builder
+    }
+}
+
+impl<S, V> Extend<(S, V)> for CaseInsensitiveMapBuilder<V>
+where
+    S: AsRef<str>,
+    V: ToTokenStream,
+{
+    /// Panics if any key (ignoring ASCII case) occurs more than once, or has already been added.
+    fn extend<I: IntoIterator<Item = (S, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.entry(key, value);
+        }
+    }
+}
+
+impl<V> ToTokenStream for CaseInsensitiveMapBuilder<V> {
+    fn to_toks(&self, tokens: &mut TokenStream) {
+        let map_toks = self.0.to_tok_stream();
+        tokens.extend(quote! { rustifact::CaseInsensitiveMap::init_raw(#map_toks) });
+    }
+}
@@ -1,6 +1,8 @@
+use super::DuplicateKeyError;
 use crate::tokens::ToTokenStream;
 use proc_macro2::TokenStream;
 use quote::quote;
+use std::collections::HashSet;
 
 /// A compile time builder for an immutable map.
 ///
@@ -10,7 +12,11 @@ use quote::quote;
 ///
 /// *This API requires the following crate feature to be activated: `map`*
 
-pub struct MapBuilder<K, V>(phf_codegen::Map<K>, std::marker::PhantomData<V>);
+pub struct MapBuilder<K, V>(
+    phf_codegen::Map<K>,
+    std::marker::PhantomData<V>,
+    HashSet<String>,
+);
 
 /// An immutable map with lookup via a perfect hash function.
 ///
@@ -27,14 +33,35 @@ where
     V: ToTokenStream,
 {
     pub fn new() -> MapBuilder<K, V> {
+        Self::with_phf_path("rustifact::internal::phf")
+    }
+
+    /// As with `new`, but the generated code refers to the `phf` crate via `path`, rather than
+    /// `rustifact::internal::phf`. Useful when `rustifact` is re-exported under another path. To the same
+    /// end, `write_phf_map_from!` accepts the path as an optional third argument.
+    pub fn with_phf_path(path: &str) -> MapBuilder<K, V> {
         let mut internal = phf_codegen::Map::new();
-        internal.phf_path("rustifact::internal::phf");
-        MapBuilder(internal, std::marker::PhantomData)
+        internal.phf_path(path);
+        MapBuilder(internal, std::marker::PhantomData, HashSet::new())
     }
 
+    /// Add an entry to the map.
+    ///
+    /// Panics if `key` has already been added.
     #[inline]
     pub fn entry(&mut self, key: K, value: V) {
+        if let Err(e) = self.try_entry(key, value) {
+            panic!("Couldn't add an entry to the MapBuilder: {}", e);
+        }
+    }
+
+    /// Add an entry to the map, returning an error if `key` has already been added.
+    pub fn try_entry(&mut self, key: K, value: V) -> Result<(), DuplicateKeyError> {
+        if !self.2.insert(key.to_tok_stream().to_string()) {
+            return Err(DuplicateKeyError::new(&key));
+        }
         self.0.entry(key, &value.to_tok_stream().to_string());
+        Ok(())
     }
 }
 
@@ -85,6 +112,16 @@ impl<K, V> Map<K, V> {
         self.0.get_entry(key)
     }
 
+    /// Look up several keys at once, returning the value (if any) for each key, in order.
+    #[inline]
+    pub fn get_many<T, const N: usize>(&self, keys: [&T; N]) -> [Option<&V>; N]
+    where
+        T: phf_shared::PhfHash + Eq + ?Sized,
+        K: phf_shared::PhfBorrow<T>,
+    {
+        keys.map(|key| self.get(key))
+    }
+
     #[inline]
     pub fn entries(&self) -> phf::map::Entries<'_, K, V> {
         self.0.entries()
@@ -100,6 +137,28 @@ impl<K, V> Map<K, V> {
         self.0.values()
     }
 
+    /// Clone the entries into a `HashMap`, which (unlike this map) can be mutated.
+    pub fn to_hash_map(&self) -> std::collections::HashMap<K, V>
+    where
+        K: Clone + Eq + std::hash::Hash,
+        V: Clone,
+    {
+        self.entries()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect()
+    }
+
+    /// Clone the entries into a `BTreeMap`, which (unlike this map) can be mutated.
+    pub fn to_btree_map(&self) -> std::collections::BTreeMap<K, V>
+    where
+        K: Clone + Ord,
+        V: Clone,
+    {
+        self.entries()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect()
+    }
+
     /// An implementation detail. You shouldn't need to call this function.
     #[inline]
     pub const fn init_raw(map: phf::Map<K, V>) -> Map<K, V> {
@@ -107,6 +166,75 @@ impl<K, V> Map<K, V> {
     }
 }
 
+impl<K, V> std::fmt::Debug for Map<K, V>
+where
+    K: std::fmt::Debug,
+    V: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Maps are equal when they have the same entries, regardless of the layout of their hash tables.
+impl<K, V> PartialEq for Map<K, V>
+where
+    K: std::hash::Hash + Eq,
+    V: PartialEq,
+{
+    fn eq(&self, other: &Map<K, V>) -> bool {
+        if self.len() != other.len() {
+            return false;
+        }
+        let other_entries: std::collections::HashMap<&K, &V> = other.entries().collect();
+        self.entries()
+            .all(|(k, v)| other_entries.get(k).is_some_and(|other_v| v == *other_v))
+    }
+}
+
+impl<K, V> Eq for Map<K, V>
+where
+    K: std::hash::Hash + Eq,
+    V: Eq,
+{
+}
+
+impl<'a, K, V> IntoIterator for &'a Map<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = phf::map::Entries<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> phf::map::Entries<'a, K, V> {
+        self.entries()
+    }
+}
+
+impl<K, V> FromIterator<(K, V)> for MapBuilder<K, V>
+where
+    K: ToTokenStream + std::hash::Hash + phf_shared::PhfHash + Eq + phf_shared::FmtConst,
+    V: ToTokenStream,
+{
+    /// Panics if any key occurs more than once.
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> MapBuilder<K, V> {
+        let mut builder = MapBuilder::new();
+        builder.extend(iter);
+        builder
+    }
+}
+
+impl<K, V> Extend<(K, V)> for MapBuilder<K, V>
+where
+    K: ToTokenStream + std::hash::Hash + phf_shared::PhfHash + Eq + phf_shared::FmtConst,
+    V: ToTokenStream,
+{
+    /// Panics if any key occurs more than once, or has already been added.
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.entry(key, value);
+        }
+    }
+}
+
 impl<K, V> ToTokenStream for MapBuilder<K, V>
 where
     K: ToTokenStream + std::hash::Hash + phf_shared::PhfHash + Eq + phf_shared::FmtConst,
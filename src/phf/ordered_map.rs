@@ -1,6 +1,9 @@
+use super::DuplicateKeyError;
 use crate::tokens::ToTokenStream;
 use proc_macro2::TokenStream;
 use quote::quote;
+use std::collections::HashSet;
+use std::ops::Range;
 
 /// A compile time builder for an order-preserving immutable map.
 ///
@@ -10,7 +13,11 @@ use quote::quote;
 /// [phf_codegen](https://crates.io/crates/phf_codegen) crate.
 ///
 /// *This API requires the following crate feature to be activated: `map`*
-pub struct OrderedMapBuilder<K, V>(phf_codegen::OrderedMap<K>, std::marker::PhantomData<V>);
+pub struct OrderedMapBuilder<K, V>(
+    phf_codegen::OrderedMap<K>,
+    std::marker::PhantomData<V>,
+    HashSet<String>,
+);
 
 /// An order-preserving immutable map with lookup via a perfect hash function.
 ///
@@ -29,14 +36,34 @@ where
     V: ToTokenStream,
 {
     pub fn new() -> OrderedMapBuilder<K, V> {
+        Self::with_phf_path("rustifact::internal::phf")
+    }
+
+    /// As with `new`, but the generated code refers to the `phf` crate via `path`, rather than
+    /// `rustifact::internal::phf`. Useful when `rustifact` is re-exported under another path.
+    pub fn with_phf_path(path: &str) -> OrderedMapBuilder<K, V> {
         let mut internal = phf_codegen::OrderedMap::new();
-        internal.phf_path("rustifact::internal::phf");
-        OrderedMapBuilder(internal, std::marker::PhantomData)
+        internal.phf_path(path);
+        OrderedMapBuilder(internal, std::marker::PhantomData, HashSet::new())
     }
 
+    /// Add an entry to the map.
+    ///
+    /// Panics if `key` has already been added.
     #[inline]
     pub fn entry(&mut self, key: K, value: V) {
+        if let Err(e) = self.try_entry(key, value) {
+            panic!("Couldn't add an entry to the OrderedMapBuilder: {}", e);
+        }
+    }
+
+    /// Add an entry to the map, returning an error if `key` has already been added.
+    pub fn try_entry(&mut self, key: K, value: V) -> Result<(), DuplicateKeyError> {
+        if !self.2.insert(key.to_tok_stream().to_string()) {
+            return Err(DuplicateKeyError::new(&key));
+        }
         self.0.entry(key, &value.to_tok_stream().to_string());
+        Ok(())
     }
 }
 
@@ -87,6 +114,16 @@ impl<K, V> OrderedMap<K, V> {
         self.0.get_entry(key)
     }
 
+    /// Look up several keys at once, returning the value (if any) for each key, in order.
+    #[inline]
+    pub fn get_many<T, const N: usize>(&self, keys: [&T; N]) -> [Option<&V>; N]
+    where
+        T: phf_shared::PhfHash + Eq + ?Sized,
+        K: phf_shared::PhfBorrow<T>,
+    {
+        keys.map(|key| self.get(key))
+    }
+
     #[inline]
     pub fn get_index<T>(&self, key: &T) -> Option<usize>
     where
@@ -101,6 +138,26 @@ impl<K, V> OrderedMap<K, V> {
         self.0.index(index)
     }
 
+    /// Iterate over the entries with indices in `range`, in order.
+    ///
+    /// Indices beyond the end of the map are ignored.
+    #[inline]
+    pub fn get_range(&self, range: Range<usize>) -> impl Iterator<Item = (&K, &V)> {
+        range.map_while(move |i| self.0.index(i))
+    }
+
+    /// The first entry of the map, or `None` if the map is empty.
+    #[inline]
+    pub fn first(&self) -> Option<(&K, &V)> {
+        self.0.index(0)
+    }
+
+    /// The last entry of the map, or `None` if the map is empty.
+    #[inline]
+    pub fn last(&self) -> Option<(&K, &V)> {
+        self.len().checked_sub(1).and_then(|i| self.0.index(i))
+    }
+
     #[inline]
     pub fn entries(&self) -> phf::ordered_map::Entries<'_, K, V> {
         self.0.entries()
@@ -116,6 +173,28 @@ impl<K, V> OrderedMap<K, V> {
         self.0.values()
     }
 
+    /// Clone the entries into a `HashMap`, which (unlike this map) can be mutated.
+    pub fn to_hash_map(&self) -> std::collections::HashMap<K, V>
+    where
+        K: Clone + Eq + std::hash::Hash,
+        V: Clone,
+    {
+        self.entries()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect()
+    }
+
+    /// Clone the entries into a `BTreeMap`, which (unlike this map) can be mutated.
+    pub fn to_btree_map(&self) -> std::collections::BTreeMap<K, V>
+    where
+        K: Clone + Ord,
+        V: Clone,
+    {
+        self.entries()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect()
+    }
+
     /// An implementation detail. You shouldn't need to call this function.
     #[inline]
     pub const fn init_raw(map: phf::OrderedMap<K, V>) -> OrderedMap<K, V> {
@@ -123,6 +202,70 @@ impl<K, V> OrderedMap<K, V> {
     }
 }
 
+impl<K, V> std::fmt::Debug for OrderedMap<K, V>
+where
+    K: std::fmt::Debug,
+    V: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Ordered maps are equal when they have the same entries, in the same order.
+impl<K, V> PartialEq for OrderedMap<K, V>
+where
+    K: PartialEq,
+    V: PartialEq,
+{
+    fn eq(&self, other: &OrderedMap<K, V>) -> bool {
+        self.len() == other.len() && self.entries().eq(other.entries())
+    }
+}
+
+impl<K, V> Eq for OrderedMap<K, V>
+where
+    K: Eq,
+    V: Eq,
+{
+}
+
+impl<'a, K, V> IntoIterator for &'a OrderedMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = phf::ordered_map::Entries<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> phf::ordered_map::Entries<'a, K, V> {
+        self.entries()
+    }
+}
+
+impl<K, V> FromIterator<(K, V)> for OrderedMapBuilder<K, V>
+where
+    K: ToTokenStream + std::hash::Hash + phf_shared::PhfHash + Eq + phf_shared::FmtConst,
+    V: ToTokenStream,
+{
+    /// Panics if any key occurs more than once.
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> OrderedMapBuilder<K, V> {
+        let mut builder = OrderedMapBuilder::new();
+        builder.extend(iter);
+        builder
+    }
+}
+
+impl<K, V> Extend<(K, V)> for OrderedMapBuilder<K, V>
+where
+    K: ToTokenStream + std::hash::Hash + phf_shared::PhfHash + Eq + phf_shared::FmtConst,
+    V: ToTokenStream,
+{
+    /// Panics if any key occurs more than once, or has already been added.
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.entry(key, value);
+        }
+    }
+}
+
 impl<K, V> ToTokenStream for OrderedMapBuilder<K, V>
 where
     K: ToTokenStream + std::hash::Hash + phf_shared::PhfHash + Eq + phf_shared::FmtConst,
@@ -1,6 +1,8 @@
+use super::DuplicateKeyError;
 use crate::tokens::ToTokenStream;
 use proc_macro2::TokenStream;
 use quote::quote;
+use std::collections::HashSet;
 
 /// A compile time builder for an order-preserving immutable set.
 ///
@@ -10,7 +12,11 @@ use quote::quote;
 /// [phf_codegen](https://crates.io/crates/phf_codegen) crate.
 ///
 /// *This API requires the following crate feature to be activated: `set`*
-pub struct OrderedSetBuilder<T>(phf_codegen::OrderedSet<T>);
+pub struct OrderedSetBuilder<T>(
+    phf_codegen::OrderedSet<T>,
+    HashSet<String>,
+    Vec<TokenStream>,
+);
 
 /// An order-preserving immutable set with lookup via a perfect hash function.
 ///
@@ -21,21 +27,43 @@ pub struct OrderedSetBuilder<T>(phf_codegen::OrderedSet<T>);
 /// [phf](https://crates.io/crates/phf) crate.
 ///
 /// *This API requires the following crate feature to be activated: `set`*
-pub struct OrderedSet<T: 'static>(phf::OrderedSet<T>);
+pub struct OrderedSet<T: 'static>(phf::OrderedSet<T>, &'static [T]);
 
 impl<T> OrderedSetBuilder<T>
 where
     T: ToTokenStream + std::hash::Hash + phf_shared::PhfHash + Eq + phf_shared::FmtConst,
 {
     pub fn new() -> OrderedSetBuilder<T> {
+        Self::with_phf_path("rustifact::internal::phf")
+    }
+
+    /// As with `new`, but the generated code refers to the `phf` crate via `path`, rather than
+    /// `rustifact::internal::phf`. Useful when `rustifact` is re-exported under another path.
+    pub fn with_phf_path(path: &str) -> OrderedSetBuilder<T> {
         let mut internal = phf_codegen::OrderedSet::new();
-        internal.phf_path("rustifact::internal::phf");
-        OrderedSetBuilder(internal)
+        internal.phf_path(path);
+        OrderedSetBuilder(internal, HashSet::new(), Vec::new())
     }
 
+    /// Add a value to the set.
+    ///
+    /// Panics if `value` has already been added.
     #[inline]
     pub fn entry(&mut self, value: T) {
+        if let Err(e) = self.try_entry(value) {
+            panic!("Couldn't add an entry to the OrderedSetBuilder: {}", e);
+        }
+    }
+
+    /// Add a value to the set, returning an error if `value` has already been added.
+    pub fn try_entry(&mut self, value: T) -> Result<(), DuplicateKeyError> {
+        let value_toks = value.to_tok_stream();
+        if !self.1.insert(value_toks.to_string()) {
+            return Err(DuplicateKeyError::new(&value));
+        }
         self.0.entry(value);
+        self.2.push(value_toks);
+        Ok(())
     }
 }
 
@@ -56,7 +84,17 @@ impl<T> OrderedSet<T> {
         U: phf_shared::PhfHash + Eq + ?Sized,
         T: phf_shared::PhfBorrow<U>,
     {
-        self.0.contains(value)
+        !self.is_empty() && self.0.contains(value)
+    }
+
+    /// Whether the set contains every one of the given values.
+    #[inline]
+    pub fn contains_all<U, const N: usize>(&self, values: [&U; N]) -> bool
+    where
+        U: phf_shared::PhfHash + Eq + ?Sized,
+        T: phf_shared::PhfBorrow<U>,
+    {
+        values.iter().all(|value| self.contains(*value))
     }
 
     #[inline]
@@ -65,6 +103,9 @@ impl<T> OrderedSet<T> {
         U: phf_shared::PhfHash + Eq + ?Sized,
         T: phf_shared::PhfBorrow<U>,
     {
+        if self.is_empty() {
+            return None;
+        }
         self.0.get_key(value)
     }
 
@@ -73,10 +114,89 @@ impl<T> OrderedSet<T> {
         self.0.iter()
     }
 
+    /// Clone the values into a `HashSet`, which (unlike this set) can be mutated.
+    pub fn to_hash_set(&self) -> std::collections::HashSet<T>
+    where
+        T: Clone + Eq + std::hash::Hash,
+    {
+        self.iter().cloned().collect()
+    }
+
+    /// Clone the values into a `BTreeSet`, which (unlike this set) can be mutated.
+    pub fn to_btree_set(&self) -> std::collections::BTreeSet<T>
+    where
+        T: Clone + Ord,
+    {
+        self.iter().cloned().collect()
+    }
+
+    /// The values of the set, in order.
+    ///
+    /// This allows values to be indexed, or (if they were added in sorted order) binary searched.
+    #[inline]
+    pub const fn as_slice(&self) -> &'static [T] {
+        self.1
+    }
+
     /// An implementation detail. You shouldn't need to call this function.
     #[inline]
-    pub const fn init_raw(set: phf::OrderedSet<T>) -> OrderedSet<T> {
-        OrderedSet(set)
+    pub const fn init_raw(set: phf::OrderedSet<T>, values: &'static [T]) -> OrderedSet<T> {
+        OrderedSet(set, values)
+    }
+}
+
+impl<T> std::fmt::Debug for OrderedSet<T>
+where
+    T: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Ordered sets are equal when they have the same values, in the same order.
+impl<T> PartialEq for OrderedSet<T>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &OrderedSet<T>) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T> Eq for OrderedSet<T> where T: Eq {}
+
+impl<'a, T> IntoIterator for &'a OrderedSet<T> {
+    type Item = &'a T;
+    type IntoIter = phf::ordered_set::Iter<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> phf::ordered_set::Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<T> FromIterator<T> for OrderedSetBuilder<T>
+where
+    T: ToTokenStream + std::hash::Hash + phf_shared::PhfHash + Eq + phf_shared::FmtConst,
+{
+    /// Panics if any value occurs more than once.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> OrderedSetBuilder<T> {
+        let mut builder = OrderedSetBuilder::new();
+        builder.extend(iter);
+        builder
+    }
+}
+
+impl<T> Extend<T> for OrderedSetBuilder<T>
+where
+    T: ToTokenStream + std::hash::Hash + phf_shared::PhfHash + Eq + phf_shared::FmtConst,
+{
+    /// Panics if any value occurs more than once, or has already been added.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.entry(value);
+        }
     }
 }
 
@@ -87,7 +207,8 @@ where
     fn to_toks(&self, tokens: &mut TokenStream) {
         let set_toks_str = self.0.build().to_string();
         if let Ok(t) = crate::internal::parse_str::<syn::Expr>(&set_toks_str) {
-            tokens.extend(quote! { rustifact::OrderedSet::init_raw(#t) });
+            let values = &self.2;
+            tokens.extend(quote! { rustifact::OrderedSet::init_raw(#t, &[#(#values),*]) });
         } else {
             panic!("Couldn't parse the expression '{}'", set_toks_str);
         }
@@ -1,6 +1,8 @@
+use super::DuplicateKeyError;
 use crate::tokens::ToTokenStream;
 use proc_macro2::TokenStream;
 use quote::quote;
+use std::collections::HashSet;
 
 /// A compile time builder for an immutable set.
 ///
@@ -10,7 +12,7 @@ use quote::quote;
 ///
 /// *This API requires the following crate feature to be activated: `set`*
 
-pub struct SetBuilder<T>(phf_codegen::Set<T>);
+pub struct SetBuilder<T>(phf_codegen::Set<T>, HashSet<String>);
 
 /// An immutable set with lookup via a perfect hash function.
 ///
@@ -26,14 +28,34 @@ where
     T: ToTokenStream + std::hash::Hash + phf_shared::PhfHash + Eq + phf_shared::FmtConst,
 {
     pub fn new() -> SetBuilder<T> {
+        Self::with_phf_path("rustifact::internal::phf")
+    }
+
+    /// As with `new`, but the generated code refers to the `phf` crate via `path`, rather than
+    /// `rustifact::internal::phf`. Useful when `rustifact` is re-exported under another path.
+    pub fn with_phf_path(path: &str) -> SetBuilder<T> {
         let mut internal = phf_codegen::Set::new();
-        internal.phf_path("rustifact::internal::phf");
-        SetBuilder(internal)
+        internal.phf_path(path);
+        SetBuilder(internal, HashSet::new())
     }
 
+    /// Add a value to the set.
+    ///
+    /// Panics if `value` has already been added.
     #[inline]
     pub fn entry(&mut self, value: T) {
+        if let Err(e) = self.try_entry(value) {
+            panic!("Couldn't add an entry to the SetBuilder: {}", e);
+        }
+    }
+
+    /// Add a value to the set, returning an error if `value` has already been added.
+    pub fn try_entry(&mut self, value: T) -> Result<(), DuplicateKeyError> {
+        if !self.1.insert(value.to_tok_stream().to_string()) {
+            return Err(DuplicateKeyError::new(&value));
+        }
         self.0.entry(value);
+        Ok(())
     }
 }
 
@@ -54,7 +76,17 @@ impl<T> Set<T> {
         U: phf_shared::PhfHash + Eq + ?Sized,
         T: phf_shared::PhfBorrow<U>,
     {
-        self.0.contains(value)
+        !self.is_empty() && self.0.contains(value)
+    }
+
+    /// Whether the set contains every one of the given values.
+    #[inline]
+    pub fn contains_all<U, const N: usize>(&self, values: [&U; N]) -> bool
+    where
+        U: phf_shared::PhfHash + Eq + ?Sized,
+        T: phf_shared::PhfBorrow<U>,
+    {
+        values.iter().all(|value| self.contains(*value))
     }
 
     #[inline]
@@ -63,6 +95,9 @@ impl<T> Set<T> {
         U: phf_shared::PhfHash + Eq + ?Sized,
         T: phf_shared::PhfBorrow<U>,
     {
+        if self.is_empty() {
+            return None;
+        }
         self.0.get_key(value)
     }
 
@@ -71,6 +106,22 @@ impl<T> Set<T> {
         self.0.iter()
     }
 
+    /// Clone the values into a `HashSet`, which (unlike this set) can be mutated.
+    pub fn to_hash_set(&self) -> std::collections::HashSet<T>
+    where
+        T: Clone + Eq + std::hash::Hash,
+    {
+        self.iter().cloned().collect()
+    }
+
+    /// Clone the values into a `BTreeSet`, which (unlike this set) can be mutated.
+    pub fn to_btree_set(&self) -> std::collections::BTreeSet<T>
+    where
+        T: Clone + Ord,
+    {
+        self.iter().cloned().collect()
+    }
+
     /// An implementation detail. You shouldn't need to call this function.
     #[inline]
     pub const fn init_raw(set: phf::Set<T>) -> Set<T> {
@@ -78,6 +129,65 @@ impl<T> Set<T> {
     }
 }
 
+impl<T> std::fmt::Debug for Set<T>
+where
+    T: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Sets are equal when they have the same values, regardless of the layout of their hash tables.
+impl<T> PartialEq for Set<T>
+where
+    T: std::hash::Hash + Eq,
+{
+    fn eq(&self, other: &Set<T>) -> bool {
+        if self.len() != other.len() {
+            return false;
+        }
+        let other_values: std::collections::HashSet<&T> = other.iter().collect();
+        self.iter().all(|value| other_values.contains(value))
+    }
+}
+
+impl<T> Eq for Set<T> where T: std::hash::Hash + Eq {}
+
+impl<'a, T> IntoIterator for &'a Set<T> {
+    type Item = &'a T;
+    type IntoIter = phf::set::Iter<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> phf::set::Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<T> FromIterator<T> for SetBuilder<T>
+where
+    T: ToTokenStream + std::hash::Hash + phf_shared::PhfHash + Eq + phf_shared::FmtConst,
+{
+    /// Panics if any value occurs more than once.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> SetBuilder<T> {
+        let mut builder = SetBuilder::new();
+        builder.extend(iter);
+        builder
+    }
+}
+
+impl<T> Extend<T> for SetBuilder<T>
+where
+    T: ToTokenStream + std::hash::Hash + phf_shared::PhfHash + Eq + phf_shared::FmtConst,
+{
+    /// Panics if any value occurs more than once, or has already been added.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.entry(value);
+        }
+    }
+}
+
 impl<T> ToTokenStream for SetBuilder<T>
 where
     T: ToTokenStream + std::hash::Hash + phf_shared::PhfHash + Eq + phf_shared::FmtConst,
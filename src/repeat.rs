@@ -0,0 +1,83 @@
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
+use quote::quote;
+
+// Split the stream of an array literal into its elements, excluding separating commas.
+pub(crate) fn split_elements(stream: TokenStream) -> Vec<TokenStream> {
+    let mut elements = Vec::new();
+    let mut current = TokenStream::new();
+    for tt in stream {
+        match &tt {
+            TokenTree::Punct(p) if p.as_char() == ',' => {
+                elements.push(std::mem::take(&mut current));
+            }
+            _ => current.extend(std::iter::once(tt)),
+        }
+    }
+    if !current.is_empty() {
+        elements.push(current);
+    }
+    elements
+}
+
+// Whether the element is a literal (possibly negated), or a boolean, and so certainly `Copy`.
+fn is_literal(element: &TokenStream) -> bool {
+    let tts: Vec<TokenTree> = element.clone().into_iter().collect();
+    match tts.as_slice() {
+        [TokenTree::Literal(_)] => true,
+        [TokenTree::Punct(p), TokenTree::Literal(_)] => p.as_char() == '-',
+        [TokenTree::Ident(i)] => i == "true" || i == "false",
+        _ => false,
+    }
+}
+
+// Returns the (possibly) compressed array literal, and the type of the array, if it's known.
+fn compress(
+    tokens: TokenStream,
+    dim: usize,
+    leaf_type: &TokenStream,
+) -> (TokenStream, Option<TokenStream>) {
+    if dim == 0 {
+        return (tokens, Some(leaf_type.clone()));
+    }
+    let mut tts = tokens.clone().into_iter();
+    let group = match (tts.next(), tts.next()) {
+        (Some(TokenTree::Group(g)), None) if g.delimiter() == Delimiter::Bracket => g,
+        _ => return (tokens, None),
+    };
+    let mut elements = Vec::new();
+    let mut element_type = if dim == 1 {
+        Some(leaf_type.clone())
+    } else {
+        None
+    };
+    for element in split_elements(group.stream()) {
+        let (element, t) = compress(element, dim - 1, leaf_type);
+        element_type = t;
+        elements.push(element);
+    }
+    let len = elements.len();
+    let array_type = element_type.as_ref().map(|t| quote! { [#t; #len] });
+    let first = elements.first().map(|e| e.to_string());
+    let uniform = len >= 2 && elements.iter().all(|e| Some(e.to_string()) == first);
+    if !uniform {
+        return (quote! { [#(#elements),*] }, array_type);
+    }
+    let element = &elements[0];
+    if is_literal(element) {
+        (quote! { [#element; #len] }, array_type)
+    } else if let Some(t) = element_type {
+        // Repeat expressions require a `Copy` element, or a constant.
+        let repeat = quote! {
+            { const __RUSTIFACT_REPEAT: #t = #element; [__RUSTIFACT_REPEAT; #len] }
+        };
+        (repeat, array_type)
+    } else {
+        (quote! { [#(#elements),*] }, array_type)
+    }
+}
+
+/// Replace arrays (at any depth of an array literal of dimension `dim`) whose elements are all
+/// identical with array-repeat expressions, such as `[0u8; 100000]`.
+pub fn compress_repeats(tokens: TokenStream, dim: usize, leaf_type: TokenStream) -> TokenStream {
+    compress(tokens, dim, &leaf_type).0
+}
@@ -0,0 +1,13 @@
+/// The fields of an enum variant generated by `write_enum!`.
+///
+/// Types (and field names) are given as strings, for example `"u32"` or `"Option<&'static str>"`,
+/// in keeping with `write_struct!`. A unit variant is specified by the absence of a `FieldSpec`, or by
+/// `FieldSpec::Discriminant` when it has an explicit discriminant.
+pub enum FieldSpec<S> {
+    /// Unnamed fields, as in `Variant(T1, T2, ...)`, given by their types.
+    Tuple(Vec<S>),
+    /// Named fields, as in `Variant { a: T1, b: T2, ... }`, given by `(name, type)` pairs.
+    Struct(Vec<(S, S)>),
+    /// No fields, but an explicit discriminant, as in `Variant = 5`, given as an expression.
+    Discriminant(S),
+}
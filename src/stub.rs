@@ -0,0 +1,167 @@
+use proc_macro2::{TokenStream, TokenTree};
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::sync::{Mutex, PoisonError};
+
+// Whether the stubs have been written. Held while they're written, so that symbols written concurrently
+// (as by `write_all!`) wait for them, rather than being overwritten by them.
+static STUBS_WRITTEN: Mutex<bool> = Mutex::new(false);
+
+/// Write a stub for each symbol imported with `use_symbols!` in the main crate.
+///
+/// Each stub is a `compile_error!` naming its symbol. Stubs are written only once per run of the
+/// build script, before any symbol is written, so written symbols overwrite their stubs. Any
+/// symbol that isn't written (whether misspelled, or no longer written but lingering in OUT_DIR
+/// from a previous run) then produces a clear compile error, rather than a missing file error or
+/// stale data.
+///
+/// Like symbols, stubs are written as part of the current [`transaction`](crate::transaction), if there
+/// is one. If it's rolled back, they're written again before the next symbol.
+///
+/// Stubs are best-effort: they're written for the symbols the main crate imported when the build script
+/// last ran. Cargo reruns the build script for edits to `src` only while the script hasn't printed any
+/// `cargo:rerun-if-changed` lines (as [`track_file`](crate::track_file) does). Such build scripts may
+/// call [`track_imports`] to keep the stubs up to date.
+pub fn write_stubs() {
+    let mut stubs_written = STUBS_WRITTEN.lock().unwrap_or_else(PoisonError::into_inner);
+    if *stubs_written {
+        return;
+    }
+    *stubs_written = true;
+    crate::manifest::reset_manifest();
+    let Ok(manifest_dir) = std::env::var("CARGO_MANIFEST_DIR") else {
+        return;
+    };
+    let mut ids = BTreeSet::new();
+    collect_imported_ids(&Path::new(&manifest_dir).join("src"), &mut ids);
+    for (ns, id, aliased) in ids {
+        let dir = match ns {
+            Some(ns) => format!("{}/{}", crate::namespace::generated_dir(), ns),
+            None => crate::namespace::generated_dir(),
+        };
+        std::fs::create_dir_all(&dir).unwrap();
+        let path_str = format!(
+            "{}/rustifact_{}_{}.rs",
+            dir,
+            std::env::var("CARGO_PKG_NAME").unwrap(),
+            id,
+        );
+        let stub = format!(
+            concat!(
+                "compile_error!(\"Symbol {} hasn't been written by the build script. ",
+                "Ensure you call write_static (or another write_... function) for {} in build.rs\");\n"
+            ),
+            id, id
+        );
+        // Aliased imports include a separate file, so it needs a stub of its own.
+        if aliased {
+            crate::transaction::write_file(crate::writer::alias_path(&path_str), &stub).unwrap();
+        }
+        crate::transaction::write_file(path_str, stub).unwrap();
+    }
+}
+
+/// Whether the stubs have been written by this run of the build script.
+pub fn stubs_written() -> bool {
+    *STUBS_WRITTEN.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Forget that the stubs were written, as their writes were rolled back by `transaction`.
+pub fn forget_stubs() {
+    *STUBS_WRITTEN.lock().unwrap_or_else(PoisonError::into_inner) = false;
+}
+
+/// Register the main crate's `src` directory as an input of the build script.
+///
+/// Once the build script registers any input (for example, via [`track_file`](crate::track_file)),
+/// Cargo no longer reruns it for edits to the main crate. Stubs for symbols imported by those edits
+/// then aren't written, so a symbol the build script doesn't write gives a missing file error rather
+/// than a clear one. Calling `track_imports` avoids this, at the cost of rerunning the build script
+/// whenever a file under `src` changes.
+///
+/// ## Further notes
+/// * Must be called from a build script (build.rs) only.
+///
+/// ## Example
+/// build.rs
+/// ```no_run
+/// use rustifact::ToTokenStream;
+///
+/// fn main() {
+///     rustifact::track_imports();
+///     let greeting = rustifact::track_file("data/greeting.txt").unwrap();
+///     rustifact::write_static!(GREETING, &'static str, greeting.trim());
+/// }
+/// ```
+pub fn track_imports() {
+    println!("cargo:rerun-if-changed=src");
+}
+
+fn collect_imported_ids(dir: &Path, ids: &mut BTreeSet<(Option<String>, String, bool)>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_imported_ids(&path, ids);
+        } else if path.extension() == Some("rs".as_ref()) {
+            if let Ok(source) = std::fs::read_to_string(&path) {
+                ids.extend(imported_ids(&source));
+            }
+        }
+    }
+}
+
+/// The identifiers passed to each invocation of `use_symbols!` in `source`, along with their
+/// namespaces, and whether they're imported under an alias.
+///
+/// The source is tokenized, so invocations in comments, doc examples and string literals are skipped.
+/// Invocations produced by other macros can't be seen, so don't get stubs.
+fn imported_ids(source: &str) -> Vec<(Option<String>, String, bool)> {
+    let mut ids = Vec::new();
+    if let Ok(tokens) = source.parse::<TokenStream>() {
+        collect_invocations(tokens, &mut ids);
+    }
+    ids
+}
+
+fn collect_invocations(tokens: TokenStream, ids: &mut Vec<(Option<String>, String, bool)>) {
+    let tts: Vec<TokenTree> = tokens.into_iter().collect();
+    for (i, tt) in tts.iter().enumerate() {
+        match tt {
+            TokenTree::Ident(id) if id == "use_symbols" => {
+                if let (Some(TokenTree::Punct(p)), Some(TokenTree::Group(args))) =
+                    (tts.get(i + 1), tts.get(i + 2))
+                {
+                    if p.as_char() == '!' {
+                        ids.extend(invocation_ids(args.stream()));
+                    }
+                }
+            }
+            TokenTree::Group(g) => collect_invocations(g.stream(), ids),
+            _ => {}
+        }
+    }
+}
+
+// The arguments may begin with a namespace (`in ns: ...`), followed by entries separated by commas.
+// Each entry is either an identifier, or an identifier and an alias: `id as alias`.
+fn invocation_ids(args: TokenStream) -> Vec<(Option<String>, String, bool)> {
+    let tts: Vec<TokenTree> = args.into_iter().collect();
+    let (ns, entries) = match tts.as_slice() {
+        [TokenTree::Ident(kw), TokenTree::Ident(ns), TokenTree::Punct(colon), entries @ ..]
+            if kw == "in" && colon.as_char() == ':' =>
+        {
+            (Some(ns.to_string()), entries)
+        }
+        entries => (None, entries),
+    };
+    entries
+        .split(|tt| matches!(tt, TokenTree::Punct(p) if p.as_char() == ','))
+        .filter_map(|entry| match entry.first() {
+            Some(TokenTree::Ident(id)) => Some((ns.clone(), id.to_string(), entry.len() > 1)),
+            _ => None,
+        })
+        .collect()
+}
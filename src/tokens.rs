@@ -1,5 +1,14 @@
-use proc_macro2::{Ident, Literal, Span, TokenStream};
+use proc_macro2::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
 use quote::{quote, TokenStreamExt};
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, BinaryHeap, HashMap, LinkedList, VecDeque};
+use std::ffi::{CStr, CString};
+use std::marker::PhantomData;
+use std::num::{Saturating, Wrapping};
+use std::ops::{Bound, ControlFlow};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::Arc;
 
 /// Provides a flexible interface for converting Rust's data types into their token stream representation.
 /// This trait is akin to `quote::ToTokens`, with a similar design, but it serves a distinct purpose.
@@ -14,7 +23,7 @@ use quote::{quote, TokenStreamExt};
 ///
 /// # Design
 ///
-/// The trait exposes three primary methods:
+/// The trait exposes the following methods:
 ///
 /// - `to_toks(&self, toks: &mut TokenStream)`: Defines how the type is converted into a token stream. This is the primary method implementers should focus on.
 ///
@@ -22,7 +31,26 @@ use quote::{quote, TokenStreamExt};
 ///
 /// - `to_tokens(&self, toks: &mut TokenStream)`: This method mirrors `to_toks` and is included for compatibility with `quote::ToTokens`.
 ///
-/// This crate also provides implementations for a range of primitive types, booleans, references, arrays, and vectors.
+/// - `to_toks_unsuffixed(&self, toks: &mut TokenStream)`: As with `to_toks`, but numeric literals are emitted without
+///   type suffixes (`5` rather than `5usize`), leaving their types to be inferred at the point of use.
+///
+/// - `is_const_safe(&self) -> bool`: Whether the emitted tokens may be evaluated in a const context, so that
+///   `write_const!` can reject (for example) heap-allocated data before generating code that fails to compile.
+///   Defaults to `true`; implementations emitting runtime constructors should return `false`.
+///
+/// - `type_toks(&self) -> Option<TokenStream>`: The type of the emitted tokens, such as `&'static str` for a `String`,
+///   if it can be determined from the value. Used to infer element types, as in `write_static_array!(ID, _ : 2, &data)`.
+///   Defaults to `None`.
+///
+/// - `type_tokens(&self) -> TokenStream`: As with `type_toks`, but panics when the type can't be determined.
+///
+/// - `to_toks_array(elements: &[Self], toks: &mut TokenStream)`: Defines how an array (or slice) of the type is
+///   converted into a token stream. Defaults to an array literal; `u8` overrides it to emit a byte string
+///   (`*b"..."`), which is far more compact.
+///
+/// This crate also provides implementations for a range of primitive types, booleans, references, arrays, vectors,
+/// tuples (of up to 16 elements), smart pointers, cells, atomics, range bounds, and the standard library's maps and
+/// queues.
 ///
 pub trait ToTokenStream {
     fn to_toks(&self, toks: &mut TokenStream);
@@ -36,6 +64,113 @@ pub trait ToTokenStream {
     fn to_tokens(&self, toks: &mut TokenStream) {
         self.to_toks(toks);
     }
+
+    fn to_toks_unsuffixed(&self, toks: &mut TokenStream) {
+        toks.extend(strip_numeric_suffixes(self.to_tok_stream()));
+    }
+
+    fn is_const_safe(&self) -> bool {
+        true
+    }
+
+    fn type_toks(&self) -> Option<TokenStream> {
+        None
+    }
+
+    fn type_tokens(&self) -> TokenStream {
+        match self.type_toks() {
+            Some(t) => t,
+            None => panic!(
+                "Couldn't determine the type of {}: its ToTokenStream implementation doesn't describe \
+                its type, or the value (for example, an empty Vec) doesn't reveal it",
+                self.to_tok_stream()
+            ),
+        }
+    }
+
+    fn to_toks_array(elements: &[Self], toks: &mut TokenStream)
+    where
+        Self: Sized,
+    {
+        to_toks_slice(elements, toks);
+    }
+}
+
+/// Convert an array of elements into a token stream, in the most compact representation for
+/// the element type.
+///
+/// This is how arrays and slices implement `ToTokenStream`. Arrays of `u8` are emitted as byte strings,
+/// so `[u8; 256]` becomes `*b"\x00\x01..."` rather than a list of 256 suffixed literals, and
+/// `[[u8; M]; N]` becomes an array of `N` byte strings.
+pub fn to_toks_array<T: ToTokenStream>(elements: &[T]) -> TokenStream {
+    let mut tokens = TokenStream::new();
+    T::to_toks_array(elements, &mut tokens);
+    tokens
+}
+
+// The type of the first element whose type can be determined. Elements such as `None` may not
+// reveal their type, so we look beyond the first.
+pub(crate) fn elements_type<'a, T, I>(elements: I) -> Option<TokenStream>
+where
+    T: ToTokenStream + 'a,
+    I: IntoIterator<Item = &'a T>,
+{
+    elements.into_iter().find_map(|a| a.type_toks())
+}
+
+fn strip_numeric_suffixes(tokens: TokenStream) -> TokenStream {
+    let mut stripped = Vec::new();
+    let mut tts = tokens.into_iter().peekable();
+    while let Some(tt) = tts.next() {
+        let tt = match tt {
+            TokenTree::Group(g) => {
+                let mut group = Group::new(g.delimiter(), strip_numeric_suffixes(g.stream()));
+                group.set_span(g.span());
+                TokenTree::Group(group)
+            }
+            // A byte string (from an array of `u8`) fixes the element type, so expand it.
+            TokenTree::Punct(p) if p.as_char() == '*' => match tts.peek().map(byte_string_value) {
+                Some(Some(bytes)) => {
+                    tts.next();
+                    let elements = bytes.into_iter().map(Literal::u8_unsuffixed);
+                    TokenTree::Group(Group::new(Delimiter::Bracket, quote! { #(#elements),* }))
+                }
+                _ => TokenTree::Punct(p),
+            },
+            TokenTree::Literal(lit) => {
+                let digits = match syn::Lit::new(lit.clone()) {
+                    // syn reads a whole float such as `1f64` as an integer with a float suffix. Without
+                    // a fractional part or exponent, its digits would read as an integer, so add one.
+                    syn::Lit::Int(i) if matches!(i.suffix(), "f32" | "f64") => {
+                        Some(format!("{}.0", i.base10_digits()))
+                    }
+                    syn::Lit::Int(i) => Some(i.base10_digits().to_string()),
+                    syn::Lit::Float(f) => match f.base10_digits() {
+                        d if d.contains(['.', 'e', 'E']) => Some(d.to_string()),
+                        d => Some(format!("{}.0", d)),
+                    },
+                    _ => None,
+                };
+                match digits.and_then(|d| d.parse::<Literal>().ok()) {
+                    Some(unsuffixed) => TokenTree::Literal(unsuffixed),
+                    None => TokenTree::Literal(lit),
+                }
+            }
+            other => other,
+        };
+        stripped.push(tt);
+    }
+    stripped.into_iter().collect()
+}
+
+pub(crate) fn byte_string_value(tt: &TokenTree) -> Option<Vec<u8>> {
+    match tt {
+        TokenTree::Literal(lit) => match syn::Lit::new(lit.clone()) {
+            syn::Lit::ByteStr(b) => Some(b.value()),
+            _ => None,
+        },
+        _ => None,
+    }
 }
 
 macro_rules! primitive {
@@ -45,6 +180,10 @@ macro_rules! primitive {
                 fn to_toks(&self, tokens: &mut TokenStream) {
                     tokens.append(Literal::$name(*self));
                 }
+
+                fn type_toks(&self) -> Option<TokenStream> {
+                    Some(quote! { $t })
+                }
             }
         )*
     };
@@ -58,50 +197,137 @@ primitive! {
     i128 => i128_suffixed
     isize => isize_suffixed
 
-    u8 => u8_suffixed
     u16 => u16_suffixed
     u32 => u32_suffixed
     u64 => u64_suffixed
     u128 => u128_suffixed
     usize => usize_suffixed
 
+    char => character
+}
+
+impl ToTokenStream for u8 {
+    fn to_toks(&self, tokens: &mut TokenStream) {
+        tokens.append(Literal::u8_suffixed(*self));
+    }
+
+    fn type_toks(&self) -> Option<TokenStream> {
+        Some(quote! { u8 })
+    }
+
+    fn to_toks_array(elements: &[Self], tokens: &mut TokenStream) {
+        let lit = Literal::byte_string(elements);
+        tokens.extend(quote! { *#lit });
+    }
+}
+
+// Non-finite values have no literal representation, so we fall back to the associated constants.
+macro_rules! float {
+    ($($t:ident => $name:ident)*) => {
+        $(
+            impl ToTokenStream for $t {
+                fn to_toks(&self, tokens: &mut TokenStream) {
+                    if self.is_nan() {
+                        tokens.extend(quote! { $t::NAN });
+                    } else if *self == $t::INFINITY {
+                        tokens.extend(quote! { $t::INFINITY });
+                    } else if *self == $t::NEG_INFINITY {
+                        tokens.extend(quote! { $t::NEG_INFINITY });
+                    } else {
+                        tokens.append(Literal::$name(*self));
+                    }
+                }
+
+                fn type_toks(&self) -> Option<TokenStream> {
+                    Some(quote! { $t })
+                }
+            }
+        )*
+    };
+}
+
+float! {
     f32 => f32_suffixed
     f64 => f64_suffixed
+}
 
-    char => character
-    &str => string
+// Literal::string escapes quotes, backslashes and control characters, so the emitted
+// literal reproduces the original string byte-for-byte.
+impl ToTokenStream for str {
+    fn to_toks(&self, tokens: &mut TokenStream) {
+        tokens.append(Literal::string(self));
+    }
+
+    fn type_toks(&self) -> Option<TokenStream> {
+        Some(quote! { &'static str })
+    }
 }
 
 impl ToTokenStream for bool {
     fn to_toks(&self, tokens: &mut TokenStream) {
         tokens.append(Ident::new(&self.to_string(), Span::call_site()));
     }
+
+    fn type_toks(&self) -> Option<TokenStream> {
+        Some(quote! { bool })
+    }
+}
+
+impl<T: ?Sized> ToTokenStream for PhantomData<T> {
+    fn to_toks(&self, tokens: &mut TokenStream) {
+        tokens.extend(quote! { ::core::marker::PhantomData });
+    }
 }
 
 impl<'a, T: ?Sized + ToTokenStream> ToTokenStream for &'a T {
     fn to_toks(&self, tokens: &mut TokenStream) {
         (**self).to_toks(tokens);
     }
+
+    fn is_const_safe(&self) -> bool {
+        (**self).is_const_safe()
+    }
+
+    fn type_toks(&self) -> Option<TokenStream> {
+        (**self).type_toks()
+    }
 }
 
 impl<'a, T: ?Sized + ToTokenStream> ToTokenStream for &'a mut T {
     fn to_toks(&self, tokens: &mut TokenStream) {
         (**self).to_toks(tokens);
     }
+
+    fn is_const_safe(&self) -> bool {
+        (**self).is_const_safe()
+    }
+
+    fn type_toks(&self) -> Option<TokenStream> {
+        (**self).type_toks()
+    }
 }
 
-fn to_toks_slice<T>(sl: &[T], tokens: &mut TokenStream)
+// The elements, each followed by a comma, in brackets. Each element is written directly into the
+// bracketed stream, avoiding a separate token stream per element, which matters for large arrays
+// of strings and other small elements.
+fn bracketed_elements<'a, T, I>(elements: I) -> TokenTree
 where
-    T: ToTokenStream,
+    T: ToTokenStream + 'a,
+    I: IntoIterator<Item = &'a T>,
 {
     let mut arr_toks = TokenStream::new();
-    for a in sl.iter() {
-        let a_toks = a.to_tok_stream();
-        let element = quote! { #a_toks, };
-        arr_toks.extend(element);
+    for a in elements {
+        a.to_toks(&mut arr_toks);
+        arr_toks.append(Punct::new(',', Spacing::Alone));
     }
-    let element = quote! { [#arr_toks] };
-    tokens.extend(element);
+    TokenTree::Group(Group::new(Delimiter::Bracket, arr_toks))
+}
+
+fn to_toks_slice<T>(sl: &[T], tokens: &mut TokenStream)
+where
+    T: ToTokenStream,
+{
+    tokens.append(bracketed_elements(sl));
 }
 
 impl<T> ToTokenStream for &[T]
@@ -109,7 +335,17 @@ where
     T: ToTokenStream,
 {
     fn to_toks(&self, tokens: &mut TokenStream) {
-        to_toks_slice(self, tokens);
+        T::to_toks_array(self, tokens);
+    }
+
+    fn is_const_safe(&self) -> bool {
+        self.iter().all(|a| a.is_const_safe())
+    }
+
+    fn type_toks(&self) -> Option<TokenStream> {
+        let t = elements_type(self.iter())?;
+        let len = self.len();
+        Some(quote! { [#t; #len] })
     }
 }
 
@@ -118,13 +354,96 @@ where
     T: ToTokenStream,
 {
     fn to_toks(&self, tokens: &mut TokenStream) {
-        to_toks_slice(self, tokens);
+        T::to_toks_array(self, tokens);
+    }
+
+    fn is_const_safe(&self) -> bool {
+        self.iter().all(|a| a.is_const_safe())
+    }
+
+    fn type_toks(&self) -> Option<TokenStream> {
+        let t = elements_type(self.iter())?;
+        let len = self.len();
+        Some(quote! { [#t; #len] })
     }
 }
 
 impl ToTokenStream for String {
     fn to_toks(&self, tokens: &mut TokenStream) {
-        tokens.extend(quote! { #self });
+        self.as_str().to_toks(tokens);
+    }
+
+    fn type_toks(&self) -> Option<TokenStream> {
+        self.as_str().type_toks()
+    }
+}
+
+fn path_str(path: &Path) -> &str {
+    match path.to_str() {
+        Some(s) => s,
+        None => panic!(
+            "Couldn't convert the path {} to tokens, as it isn't valid UTF-8",
+            path.display()
+        ),
+    }
+}
+
+/// Maps to a `&std::path::Path` (as `Path` is unsized, it's emitted behind a reference).
+///
+/// Only paths that are valid UTF-8 are supported; `to_toks` panics otherwise. As `Path::new` isn't a
+/// const function, it's suitable for `write_fn!` but not `write_const!` or `write_static!`.
+impl ToTokenStream for Path {
+    fn to_toks(&self, tokens: &mut TokenStream) {
+        let s = path_str(self);
+        tokens.extend(quote! { std::path::Path::new(#s) });
+    }
+
+    fn is_const_safe(&self) -> bool {
+        false
+    }
+}
+
+/// Maps to a `std::path::PathBuf` built at runtime.
+///
+/// Only paths that are valid UTF-8 are supported; `to_toks` panics otherwise. A `PathBuf` owns its buffer,
+/// so can only be built at runtime: use `write_fn!`, rather than `write_const!` or `write_static!`.
+impl ToTokenStream for PathBuf {
+    fn to_toks(&self, tokens: &mut TokenStream) {
+        let s = path_str(self);
+        tokens.extend(quote! { std::path::PathBuf::from(#s) });
+    }
+
+    fn is_const_safe(&self) -> bool {
+        false
+    }
+}
+
+/// Maps to a `&'static std::ffi::CStr`, constructed in a const context from a byte string with an explicit
+/// trailing NUL, validated via `CStr::from_bytes_with_nul`.
+impl ToTokenStream for CStr {
+    fn to_toks(&self, tokens: &mut TokenStream) {
+        let lit = Literal::byte_string(self.to_bytes_with_nul());
+        tokens.extend(quote! {
+            match std::ffi::CStr::from_bytes_with_nul(#lit) {
+                Ok(s) => s,
+                Err(_) => panic!("invalid C string"),
+            }
+        });
+    }
+
+    fn type_toks(&self) -> Option<TokenStream> {
+        Some(quote! { &'static std::ffi::CStr })
+    }
+}
+
+/// Maps to a `&'static std::ffi::CStr`, as with `CStr`. Call `to_owned` at runtime for a `CString`.
+impl ToTokenStream for CString {
+    fn to_toks(&self, tokens: &mut TokenStream) {
+        self.as_c_str().to_toks(tokens);
+    }
+
+    fn type_toks(&self) -> Option<TokenStream> {
+        self.as_c_str().type_toks()
     }
 }
 
@@ -133,17 +452,23 @@ where
     T: ToTokenStream,
 {
     fn to_toks(&self, tokens: &mut TokenStream) {
-        let mut arr_toks = TokenStream::new();
-        for a in self {
-            let a_toks = a.to_tok_stream();
-            let element = quote! { #a_toks, };
-            arr_toks.extend(element);
-        }
-        let element = quote! { vec![#arr_toks] };
-        tokens.extend(element);
+        let arr_toks = bracketed_elements(self);
+        tokens.extend(quote! { vec! #arr_toks });
+    }
+
+    fn is_const_safe(&self) -> bool {
+        false
+    }
+
+    fn type_toks(&self) -> Option<TokenStream> {
+        let t = elements_type(self.iter())?;
+        Some(quote! { Vec<#t> })
     }
 }
 
+/// Maps to `Some(...)` or a bare `None`. The bare `None` relies on the declared type of the
+/// surrounding `const` or `static` (or a sibling `Some` element) for inference, which is always
+/// present in the emitted declaration.
 impl<T> ToTokenStream for Option<T>
 where
     T: ToTokenStream,
@@ -163,164 +488,465 @@ where
         }
         tokens.extend(element);
     }
+
+    fn is_const_safe(&self) -> bool {
+        self.as_ref().is_none_or(|a| a.is_const_safe())
+    }
+
+    fn type_toks(&self) -> Option<TokenStream> {
+        let t = self.as_ref()?.type_toks()?;
+        Some(quote! { Option<#t> })
+    }
 }
 
-macro_rules! build_tuple_trait {
-    ($($id:ident),+;$($index:literal),+) => {
-        fn to_toks(&self, tokens: &mut TokenStream) {
-            // As of Rust 1.69, limitations in the macro system mean
-            // we can't use tuple indexing with the form self.$index,
-            // so we destructure and use shadowing instead.
-            let ($($id),+) = self;
-            $(let $id = $id.to_tok_stream();)+
-            let element = quote! { ($(#$id),+) };
-            tokens.extend(element);
+/// Maps to a `std::ops::Bound`, such as `std::ops::Bound::Included(5u32)`.
+impl<T> ToTokenStream for Bound<T>
+where
+    T: ToTokenStream,
+{
+    fn to_toks(&self, tokens: &mut TokenStream) {
+        let element = match self {
+            Bound::Included(a) => {
+                let a_toks = a.to_tok_stream();
+                quote! { std::ops::Bound::Included(#a_toks) }
+            }
+            Bound::Excluded(a) => {
+                let a_toks = a.to_tok_stream();
+                quote! { std::ops::Bound::Excluded(#a_toks) }
+            }
+            Bound::Unbounded => quote! { std::ops::Bound::Unbounded },
+        };
+        tokens.extend(element);
+    }
+
+    fn is_const_safe(&self) -> bool {
+        match self {
+            Bound::Included(a) | Bound::Excluded(a) => a.is_const_safe(),
+            Bound::Unbounded => true,
+        }
+    }
+}
+
+/// Maps to a `std::ops::ControlFlow`, such as `std::ops::ControlFlow::Break(5u32)`.
+impl<B, C> ToTokenStream for ControlFlow<B, C>
+where
+    B: ToTokenStream,
+    C: ToTokenStream,
+{
+    fn to_toks(&self, tokens: &mut TokenStream) {
+        let element = match self {
+            ControlFlow::Continue(c) => {
+                let c_toks = c.to_tok_stream();
+                quote! { std::ops::ControlFlow::Continue(#c_toks) }
+            }
+            ControlFlow::Break(b) => {
+                let b_toks = b.to_tok_stream();
+                quote! { std::ops::ControlFlow::Break(#b_toks) }
+            }
+        };
+        tokens.extend(element);
+    }
+
+    fn is_const_safe(&self) -> bool {
+        match self {
+            ControlFlow::Continue(c) => c.is_const_safe(),
+            ControlFlow::Break(b) => b.is_const_safe(),
         }
+    }
+}
+
+macro_rules! smart_pointer {
+    ($($t:ident => $path:path)*) => {
+        $(
+            /// Maps to a pointer constructed at runtime, via `From`, so that unsized
+            /// pointees such as `str` are also supported.
+            ///
+            /// The pointee is moved to the heap when the pointer is constructed, which can't happen in a
+            /// constant, so these pointers may be written by `write_fn!` but not `write_const!` or
+            /// `write_static!`.
+            impl<T: ?Sized + ToTokenStream> ToTokenStream for $t<T> {
+                fn to_toks(&self, tokens: &mut TokenStream) {
+                    let inner = (**self).to_tok_stream();
+                    tokens.extend(quote! { $path::from(#inner) });
+                }
+
+                fn is_const_safe(&self) -> bool {
+                    false
+                }
+            }
+        )*
     };
 }
 
-impl<T1, T2> ToTokenStream for (T1, T2)
+smart_pointer! {
+    Box => std::boxed::Box
+    Rc => std::rc::Rc
+    Arc => std::sync::Arc
+}
+
+/// A type-erased value, whose tokens are emitted as they are.
+///
+/// `Box<dyn ToTokenStream>` is itself a `ToTokenStream`, but, like any boxed value, maps to a runtime
+/// `Box`. `DynTokens` maps to the tokens of the value it holds instead, so values of differing types in the
+/// build script may be collected (for example, into a `Vec<DynTokens>`) and exported together, provided that
+/// their tokens have a common type.
+///
+/// ## Example
+/// build.rs
+/// ```no_run
+/// use rustifact::{DynTokens, ToTokenStream};
+///
+/// fn main() {
+///     let names = vec![
+///         DynTokens::new(String::from("owned")),
+///         DynTokens::new("borrowed"),
+///         DynTokens::new('c'.to_string()),
+///     ];
+///     rustifact::write_static_array!(NAMES, &'static str, &names);
+/// }
+/// ```
+pub struct DynTokens(pub Box<dyn ToTokenStream>);
+
+impl DynTokens {
+    pub fn new<T: ToTokenStream + 'static>(value: T) -> DynTokens {
+        DynTokens(Box::new(value))
+    }
+}
+
+impl From<Box<dyn ToTokenStream>> for DynTokens {
+    fn from(value: Box<dyn ToTokenStream>) -> DynTokens {
+        DynTokens(value)
+    }
+}
+
+impl ToTokenStream for DynTokens {
+    fn to_toks(&self, tokens: &mut TokenStream) {
+        (*self.0).to_toks(tokens);
+    }
+
+    fn is_const_safe(&self) -> bool {
+        (*self.0).is_const_safe()
+    }
+
+    fn type_toks(&self) -> Option<TokenStream> {
+        (*self.0).type_toks()
+    }
+}
+
+macro_rules! num_wrapper {
+    ($($t:ident => $path:path)*) => {
+        $(
+            /// Maps to the wrapper's constructor, so is suitable for any of the `write_...` macros.
+            impl<T: ToTokenStream> ToTokenStream for $t<T> {
+                fn to_toks(&self, tokens: &mut TokenStream) {
+                    let inner = self.0.to_tok_stream();
+                    tokens.extend(quote! { $path(#inner) });
+                }
+
+                fn is_const_safe(&self) -> bool {
+                    self.0.is_const_safe()
+                }
+            }
+        )*
+    };
+}
+
+num_wrapper! {
+    Wrapping => std::num::Wrapping
+    Saturating => std::num::Saturating
+}
+
+/// Maps to a `std::cell::Cell` holding the current value.
+///
+/// As a `Cell` isn't `Sync`, it can't be placed in a `static`, so it's suitable for `write_fn!` (or
+/// nested in data written by `write_fn!`), but not `write_static!`.
+impl<T> ToTokenStream for Cell<T>
 where
-    T1: ToTokenStream,
-    T2: ToTokenStream,
+    T: ToTokenStream + Copy,
 {
-    build_tuple_trait!(t1, t2; 0, 1);
+    fn to_toks(&self, tokens: &mut TokenStream) {
+        let inner = self.get().to_tok_stream();
+        tokens.extend(quote! { std::cell::Cell::new(#inner) });
+    }
+
+    fn is_const_safe(&self) -> bool {
+        self.get().is_const_safe()
+    }
+}
+
+/// Maps to a `std::cell::RefCell` holding the current value.
+///
+/// As a `RefCell` isn't `Sync`, it can't be placed in a `static`, so it's suitable for `write_fn!` (or
+/// nested in data written by `write_fn!`), but not `write_static!`. Panics if the value is currently
+/// mutably borrowed.
+impl<T> ToTokenStream for RefCell<T>
+where
+    T: ToTokenStream,
+{
+    fn to_toks(&self, tokens: &mut TokenStream) {
+        let inner = self.borrow().to_tok_stream();
+        tokens.extend(quote! { std::cell::RefCell::new(#inner) });
+    }
+
+    fn is_const_safe(&self) -> bool {
+        self.borrow().is_const_safe()
+    }
+}
+
+macro_rules! atomic {
+    ($($t:ident)*) => {
+        $(
+            /// Maps to an atomic initialised with the current value, read with `Ordering::Relaxed`.
+            ///
+            /// Intended for `write_static!`, giving a single shared atomic. The tokens are also accepted by
+            /// `write_const!`, but each use of a const creates a fresh atomic, so updates to it are lost.
+            impl ToTokenStream for std::sync::atomic::$t {
+                fn to_toks(&self, tokens: &mut TokenStream) {
+                    let inner = self
+                        .load(std::sync::atomic::Ordering::Relaxed)
+                        .to_tok_stream();
+                    tokens.extend(quote! { std::sync::atomic::$t::new(#inner) });
+                }
+
+                fn type_toks(&self) -> Option<TokenStream> {
+                    Some(quote! { std::sync::atomic::$t })
+                }
+            }
+        )*
+    };
+}
+
+atomic! {
+    AtomicBool
+    AtomicI8 AtomicI16 AtomicI32 AtomicI64 AtomicIsize
+    AtomicU8 AtomicU16 AtomicU32 AtomicU64 AtomicUsize
 }
 
-impl<T1, T2, T3> ToTokenStream for (T1, T2, T3)
+fn to_toks_seq<'a, T, I>(seq_path: TokenStream, elements: I, tokens: &mut TokenStream)
 where
-    T1: ToTokenStream,
-    T2: ToTokenStream,
-    T3: ToTokenStream,
+    T: ToTokenStream + 'a,
+    I: Iterator<Item = &'a T>,
 {
-    build_tuple_trait!(t1, t2, t3; 0, 1, 2);
+    let arr_toks = bracketed_elements(elements);
+    tokens.extend(quote! { #seq_path::from(#arr_toks) });
 }
 
-impl<T1, T2, T3, T4> ToTokenStream for (T1, T2, T3, T4)
+/// Maps to a `std::collections::VecDeque` with elements in front-to-back order.
+///
+/// As a `VecDeque` allocates its ring buffer, it's suitable for `write_fn!` but not `write_const!` or
+/// `write_static!`.
+impl<T> ToTokenStream for VecDeque<T>
 where
-    T1: ToTokenStream,
-    T2: ToTokenStream,
-    T3: ToTokenStream,
-    T4: ToTokenStream,
+    T: ToTokenStream,
 {
-    build_tuple_trait!(t1, t2, t3, t4; 0, 1, 2, 3);
+    fn to_toks(&self, tokens: &mut TokenStream) {
+        to_toks_seq(quote! { std::collections::VecDeque }, self.iter(), tokens);
+    }
+
+    fn is_const_safe(&self) -> bool {
+        false
+    }
 }
 
-impl<T1, T2, T3, T4, T5> ToTokenStream for (T1, T2, T3, T4, T5)
+/// Maps to a `std::collections::LinkedList` with elements in front-to-back order.
+///
+/// Each node of a `LinkedList` is a separate allocation, so the list must be written with `write_fn!`, rather
+/// than `write_const!` or `write_static!`.
+impl<T> ToTokenStream for LinkedList<T>
 where
-    T1: ToTokenStream,
-    T2: ToTokenStream,
-    T3: ToTokenStream,
-    T4: ToTokenStream,
-    T5: ToTokenStream,
+    T: ToTokenStream,
 {
-    build_tuple_trait!(t1, t2, t3, t4, t5; 0, 1, 2, 3, 4);
+    fn to_toks(&self, tokens: &mut TokenStream) {
+        to_toks_seq(quote! { std::collections::LinkedList }, self.iter(), tokens);
+    }
+
+    fn is_const_safe(&self) -> bool {
+        false
+    }
 }
 
-impl<T1, T2, T3, T4, T5, T6> ToTokenStream for (T1, T2, T3, T4, T5, T6)
+/// Maps to a `std::collections::BinaryHeap` rebuilt from the underlying vector.
+///
+/// As a `BinaryHeap` allocates (and `BinaryHeap::from` reorders the elements at runtime), it's suitable for
+/// `write_fn!` but not `write_const!` or `write_static!`.
+impl<T> ToTokenStream for BinaryHeap<T>
 where
-    T1: ToTokenStream,
-    T2: ToTokenStream,
-    T3: ToTokenStream,
-    T4: ToTokenStream,
-    T5: ToTokenStream,
-    T6: ToTokenStream,
+    T: ToTokenStream,
 {
-    build_tuple_trait!(t1, t2, t3, t4, t5, t6; 0, 1, 2, 3, 4, 5);
+    fn to_toks(&self, tokens: &mut TokenStream) {
+        let mut arr_toks = TokenStream::new();
+        for a in self.iter() {
+            let a_toks = a.to_tok_stream();
+            arr_toks.extend(quote! { #a_toks, });
+        }
+        tokens.extend(quote! { std::collections::BinaryHeap::from(vec![#arr_toks]) });
+    }
+
+    fn is_const_safe(&self) -> bool {
+        false
+    }
 }
 
-impl<T1, T2, T3, T4, T5, T6, T7> ToTokenStream for (T1, T2, T3, T4, T5, T6, T7)
+/// Sort `items` by the string representation of the tokens that `key` gives for each.
+///
+/// Anything emitted from an unordered collection, such as a `HashMap`, should be sorted this way, so that
+/// rebuilds produce identical output, and don't trigger needless recompilation of the main crate.
+pub fn sort_by_tokens<T, F>(items: &mut [T], key: F)
 where
-    T1: ToTokenStream,
-    T2: ToTokenStream,
-    T3: ToTokenStream,
-    T4: ToTokenStream,
-    T5: ToTokenStream,
-    T6: ToTokenStream,
-    T7: ToTokenStream,
+    F: Fn(&T) -> TokenStream,
 {
-    build_tuple_trait!(t1, t2, t3, t4, t5, t6, t7; 0, 1, 2, 3, 4, 5, 6);
+    items.sort_by_cached_key(|item| key(item).to_string());
 }
 
-impl<T1, T2, T3, T4, T5, T6, T7, T8> ToTokenStream for (T1, T2, T3, T4, T5, T6, T7, T8)
+/// The key and value types of a map with the given `entries`, as determined by `type_toks`.
+///
+/// Panics if either can't be determined, as when the map is empty.
+pub fn entry_types<K, V>(entries: &[(K, V)]) -> (TokenStream, TokenStream)
 where
-    T1: ToTokenStream,
-    T2: ToTokenStream,
-    T3: ToTokenStream,
-    T4: ToTokenStream,
-    T5: ToTokenStream,
-    T6: ToTokenStream,
-    T7: ToTokenStream,
-    T8: ToTokenStream,
+    K: ToTokenStream,
+    V: ToTokenStream,
 {
-    build_tuple_trait!(t1, t2, t3, t4, t5, t6, t7, t8; 0, 1, 2, 3, 4, 5, 6, 7);
+    let k = elements_type(entries.iter().map(|(k, _)| k));
+    let v = elements_type(entries.iter().map(|(_, v)| v));
+    match (k, v) {
+        (Some(k), Some(v)) => (k, v),
+        _ => panic!(
+            "Couldn't determine the key and value types of the map: the ToTokenStream implementations \
+            don't describe them, or the map (for example, an empty map) doesn't reveal them"
+        ),
+    }
 }
 
-impl<T1, T2, T3, T4, T5, T6, T7, T8, T9> ToTokenStream for (T1, T2, T3, T4, T5, T6, T7, T8, T9)
+fn map_type<'a, K, V, I>(map_path: TokenStream, entries: I) -> Option<TokenStream>
 where
-    T1: ToTokenStream,
-    T2: ToTokenStream,
-    T3: ToTokenStream,
-    T4: ToTokenStream,
-    T5: ToTokenStream,
-    T6: ToTokenStream,
-    T7: ToTokenStream,
-    T8: ToTokenStream,
-    T9: ToTokenStream,
+    K: ToTokenStream + 'a,
+    V: ToTokenStream + 'a,
+    I: Iterator<Item = (&'a K, &'a V)> + Clone,
 {
-    build_tuple_trait!(t1, t2, t3, t4, t5, t6, t7, t8, t9; 0, 1, 2, 3, 4, 5, 6, 7, 8);
+    let k = elements_type(entries.clone().map(|(k, _)| k))?;
+    let v = elements_type(entries.map(|(_, v)| v))?;
+    Some(quote! { #map_path<#k, #v> })
 }
 
-impl<T1, T2, T3, T4, T5, T6, T7, T8, T9, T10> ToTokenStream
-    for (T1, T2, T3, T4, T5, T6, T7, T8, T9, T10)
+fn to_toks_map<'a, K, V, I>(map_path: TokenStream, entries: I, tokens: &mut TokenStream)
 where
-    T1: ToTokenStream,
-    T2: ToTokenStream,
-    T3: ToTokenStream,
-    T4: ToTokenStream,
-    T5: ToTokenStream,
-    T6: ToTokenStream,
-    T7: ToTokenStream,
-    T8: ToTokenStream,
-    T9: ToTokenStream,
-    T10: ToTokenStream,
+    K: ToTokenStream + 'a,
+    V: ToTokenStream + 'a,
+    I: Iterator<Item = (&'a K, &'a V)>,
 {
-    build_tuple_trait!(t1, t2, t3, t4, t5, t6, t7, t8, t9, t10; 0, 1, 2, 3, 4, 5, 6, 7, 8, 9);
+    let mut entries_toks = TokenStream::new();
+    for (k, v) in entries {
+        let k_toks = k.to_tok_stream();
+        let v_toks = v.to_tok_stream();
+        entries_toks.extend(quote! { (#k_toks, #v_toks), });
+    }
+    tokens.extend(quote! { #map_path::from([#entries_toks]) });
 }
 
-impl<T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11> ToTokenStream
-    for (T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11)
+/// Maps to a `std::collections::HashMap` built at runtime.
+///
+/// Entries are emitted sorted by the token representation of their keys, so that rebuilds produce
+/// identical output. The entries are hashed with a randomly seeded hasher when the map is built, so it's
+/// suitable for `write_fn!` but not `write_const!` or `write_static!`.
+impl<K, V> ToTokenStream for HashMap<K, V>
 where
-    T1: ToTokenStream,
-    T2: ToTokenStream,
-    T3: ToTokenStream,
-    T4: ToTokenStream,
-    T5: ToTokenStream,
-    T6: ToTokenStream,
-    T7: ToTokenStream,
-    T8: ToTokenStream,
-    T9: ToTokenStream,
-    T10: ToTokenStream,
-    T11: ToTokenStream,
+    K: ToTokenStream,
+    V: ToTokenStream,
 {
-    build_tuple_trait!(t1, t2, t3, t4, t5, t6, t7, t8, t9, t10, t11; 0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10);
+    fn to_toks(&self, tokens: &mut TokenStream) {
+        let mut entries: Vec<(&K, &V)> = self.iter().collect();
+        sort_by_tokens(&mut entries, |(k, _)| k.to_tok_stream());
+        to_toks_map(
+            quote! { std::collections::HashMap },
+            entries.into_iter(),
+            tokens,
+        );
+    }
+
+    fn is_const_safe(&self) -> bool {
+        false
+    }
+
+    fn type_toks(&self) -> Option<TokenStream> {
+        map_type(quote! { std::collections::HashMap }, self.iter())
+    }
 }
 
-impl<T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12> ToTokenStream
-    for (T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12)
+/// Maps to a `std::collections::BTreeMap` built at runtime.
+///
+/// A `BTreeMap` allocates its nodes as entries are inserted, so it may be written by `write_fn!`, but not
+/// `write_const!` or `write_static!`.
+impl<K, V> ToTokenStream for BTreeMap<K, V>
 where
-    T1: ToTokenStream,
-    T2: ToTokenStream,
-    T3: ToTokenStream,
-    T4: ToTokenStream,
-    T5: ToTokenStream,
-    T6: ToTokenStream,
-    T7: ToTokenStream,
-    T8: ToTokenStream,
-    T9: ToTokenStream,
-    T10: ToTokenStream,
-    T11: ToTokenStream,
-    T12: ToTokenStream,
+    K: ToTokenStream,
+    V: ToTokenStream,
 {
-    build_tuple_trait!(t1, t2, t3, t4, t5, t6, t7, t8, t9, t10, t11, t12; 0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11);
+    fn to_toks(&self, tokens: &mut TokenStream) {
+        to_toks_map(quote! { std::collections::BTreeMap }, self.iter(), tokens);
+    }
+
+    fn is_const_safe(&self) -> bool {
+        false
+    }
+
+    fn type_toks(&self) -> Option<TokenStream> {
+        map_type(quote! { std::collections::BTreeMap }, self.iter())
+    }
+}
+
+macro_rules! build_tuple_trait {
+    ($($id:ident),+;$($index:literal),+) => {
+        fn to_toks(&self, tokens: &mut TokenStream) {
+            // As of Rust 1.69, limitations in the macro system mean
+            // we can't use tuple indexing with the form self.$index,
+            // so we destructure and use shadowing instead.
+            let ($($id),+) = self;
+            $(let $id = $id.to_tok_stream();)+
+            let element = quote! { ($(#$id),+) };
+            tokens.extend(element);
+        }
+
+        fn is_const_safe(&self) -> bool {
+            let ($($id),+) = self;
+            $($id.is_const_safe())&&+
+        }
+
+        fn type_toks(&self) -> Option<TokenStream> {
+            let ($($id),+) = self;
+            $(let $id = $id.type_toks()?;)+
+            Some(quote! { ($(#$id),+) })
+        }
+    };
+}
+
+// Generates an impl for each tuple arity from 2 up to and including 16.
+macro_rules! tuple_impls {
+    ($(($($t:ident $id:ident $index:literal),+))+) => {
+        $(
+            impl<$($t),+> ToTokenStream for ($($t),+)
+            where
+                $($t: ToTokenStream),+
+            {
+                build_tuple_trait!($($id),+; $($index),+);
+            }
+        )+
+    };
+}
+
+tuple_impls! {
+    (T1 t1 0, T2 t2 1)
+    (T1 t1 0, T2 t2 1, T3 t3 2)
+    (T1 t1 0, T2 t2 1, T3 t3 2, T4 t4 3)
+    (T1 t1 0, T2 t2 1, T3 t3 2, T4 t4 3, T5 t5 4)
+    (T1 t1 0, T2 t2 1, T3 t3 2, T4 t4 3, T5 t5 4, T6 t6 5)
+    (T1 t1 0, T2 t2 1, T3 t3 2, T4 t4 3, T5 t5 4, T6 t6 5, T7 t7 6)
+    (T1 t1 0, T2 t2 1, T3 t3 2, T4 t4 3, T5 t5 4, T6 t6 5, T7 t7 6, T8 t8 7)
+    (T1 t1 0, T2 t2 1, T3 t3 2, T4 t4 3, T5 t5 4, T6 t6 5, T7 t7 6, T8 t8 7, T9 t9 8)
+    (T1 t1 0, T2 t2 1, T3 t3 2, T4 t4 3, T5 t5 4, T6 t6 5, T7 t7 6, T8 t8 7, T9 t9 8, T10 t10 9)
+    (T1 t1 0, T2 t2 1, T3 t3 2, T4 t4 3, T5 t5 4, T6 t6 5, T7 t7 6, T8 t8 7, T9 t9 8, T10 t10 9, T11 t11 10)
+    (T1 t1 0, T2 t2 1, T3 t3 2, T4 t4 3, T5 t5 4, T6 t6 5, T7 t7 6, T8 t8 7, T9 t9 8, T10 t10 9, T11 t11 10, T12 t12 11)
+    (T1 t1 0, T2 t2 1, T3 t3 2, T4 t4 3, T5 t5 4, T6 t6 5, T7 t7 6, T8 t8 7, T9 t9 8, T10 t10 9, T11 t11 10, T12 t12 11, T13 t13 12)
+    (T1 t1 0, T2 t2 1, T3 t3 2, T4 t4 3, T5 t5 4, T6 t6 5, T7 t7 6, T8 t8 7, T9 t9 8, T10 t10 9, T11 t11 10, T12 t12 11, T13 t13 12, T14 t14 13)
+    (T1 t1 0, T2 t2 1, T3 t3 2, T4 t4 3, T5 t5 4, T6 t6 5, T7 t7 6, T8 t8 7, T9 t9 8, T10 t10 9, T11 t11 10, T12 t12 11, T13 t13 12, T14 t14 13, T15 t15 14)
+    (T1 t1 0, T2 t2 1, T3 t3 2, T4 t4 3, T5 t5 4, T6 t6 5, T7 t7 6, T8 t8 7, T9 t9 8, T10 t10 9, T11 t11 10, T12 t12 11, T13 t13 12, T14 t14 13, T15 t15 14, T16 t16 15)
 }
@@ -0,0 +1,154 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+
+// The files written during the current transaction, as (temporary path, final path) pairs.
+static PENDING: Mutex<Option<Vec<(PathBuf, PathBuf)>>> = Mutex::new(None);
+
+/// Write symbols from the build script all at once, or not at all.
+///
+/// Files written by `write_`... macros within `f` are written to temporary paths, and moved into place only
+/// once `f` returns. If `f` panics, the temporary files are removed instead, so that `OUT_DIR` isn't left
+/// with a mix of new and old symbols. Transactions may be nested, in which case the inner transactions
+/// are part of the outermost one.
+///
+/// Files are moved into place with `std::fs::rename`. As they're moved within `OUT_DIR`, each move is
+/// atomic on common platforms.
+///
+/// ## Further notes
+/// * Must be called from a build script (build.rs) only.
+///
+/// ## Example
+/// build.rs
+/// ```no_run
+/// use rustifact::ToTokenStream;
+///
+/// fn main() {
+///     let widths = vec![("NARROW", 10u32), ("WIDE", 100u32)];
+///     rustifact::transaction(|| {
+///         rustifact::write_consts!(public, WIDTHS, u32, &widths);
+///         rustifact::write_const!(MAX_WIDTH, u32, &100u32);
+///     });
+/// }
+/// ```
+pub fn transaction<R, F: FnOnce() -> R>(f: F) -> R {
+    // If the stubs are first written within the transaction, they must be written again after a rollback.
+    let stubs_written = crate::stub::stubs_written();
+    {
+        let mut pending = PENDING.lock().unwrap_or_else(PoisonError::into_inner);
+        if pending.is_some() {
+            drop(pending);
+            return f();
+        }
+        *pending = Some(Vec::new());
+    }
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    let files = PENDING
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .take()
+        .unwrap_or_default();
+    match result {
+        Ok(r) => {
+            for (temp, path) in files {
+                std::fs::rename(&temp, &path).unwrap();
+            }
+            r
+        }
+        Err(e) => {
+            for (temp, path) in files {
+                let _ = std::fs::remove_file(temp);
+                crate::validate::forget_written(&path);
+            }
+            if !stubs_written {
+                crate::stub::forget_stubs();
+            }
+            crate::manifest::reload_symbols();
+            panic::resume_unwind(e)
+        }
+    }
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    let mut s = path.as_os_str().to_owned();
+    s.push(".rustifact-tmp");
+    PathBuf::from(s)
+}
+
+/// Write a file via `f`, in the current transaction if there is one.
+///
+/// The file is written to a temporary path and then moved into place, so that a partially written
+/// file is never observed.
+pub fn write_file_with<P, F>(path: P, f: F) -> io::Result<()>
+where
+    P: AsRef<Path>,
+    F: FnOnce(&mut BufWriter<File>) -> io::Result<()>,
+{
+    let path = path.as_ref();
+    let temp = temp_path(path);
+    let mut writer = BufWriter::new(File::create(&temp)?);
+    f(&mut writer)?;
+    writer.flush()?;
+    drop(writer);
+    let mut pending = PENDING.lock().unwrap_or_else(PoisonError::into_inner);
+    match pending.as_mut() {
+        Some(files) => {
+            files.retain(|(_, p)| p != path);
+            files.push((temp, path.to_path_buf()));
+            Ok(())
+        }
+        None => {
+            drop(pending);
+            std::fs::rename(&temp, path)
+        }
+    }
+}
+
+/// Write a file, in the current transaction if there is one.
+pub fn write_file<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> io::Result<()> {
+    write_file_with(path, |w| w.write_all(contents.as_ref()))
+}
+
+/// Append to a file, in the current transaction if there is one.
+///
+/// Within a transaction, the first append copies the file to its temporary path, and later appends extend
+/// the copy, so that a file appended to many times is copied once.
+pub fn append_file<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> io::Result<()> {
+    let path = path.as_ref();
+    let mut pending = PENDING.lock().unwrap_or_else(PoisonError::into_inner);
+    let target = match pending.as_mut() {
+        Some(files) => match files.iter().find(|(_, p)| p == path) {
+            Some((temp, _)) => temp.clone(),
+            None => {
+                let temp = temp_path(path);
+                match std::fs::copy(path, &temp) {
+                    Ok(_) => {}
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                        File::create(&temp)?;
+                    }
+                    Err(e) => return Err(e),
+                }
+                files.push((temp.clone(), path.to_path_buf()));
+                temp
+            }
+        },
+        None => path.to_path_buf(),
+    };
+    drop(pending);
+    let mut file = OpenOptions::new().create(true).append(true).open(target)?;
+    file.write_all(contents.as_ref())
+}
+
+/// Read a file written by the build script, including one written earlier in the current transaction.
+pub fn read_file<P: AsRef<Path>>(path: P) -> io::Result<String> {
+    let path = path.as_ref();
+    let pending = PENDING.lock().unwrap_or_else(PoisonError::into_inner);
+    let temp = pending
+        .as_ref()
+        .and_then(|files| files.iter().find(|(_, p)| p == path))
+        .map(|(temp, _)| temp.clone());
+    drop(pending);
+    std::fs::read_to_string(temp.as_deref().unwrap_or(path))
+}
@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, PoisonError};
+
+// Whether parse errors in generated code are left for `validate_all` to report.
+static DEFERRED: AtomicBool = AtomicBool::new(false);
+
+// The files written by this run of the build script, and the symbols they hold.
+static WRITTEN: Mutex<Option<HashMap<PathBuf, String>>> = Mutex::new(None);
+
+/// Don't panic on the first symbol whose generated code fails to parse; leave it for [`validate_all`].
+///
+/// By default, writing a symbol whose code fails to parse panics immediately. After calling
+/// `defer_validation`, the unformatted code is written and the build script carries on, so that a
+/// single call to `validate_all` reports every such symbol.
+///
+/// ## Further notes
+/// * Must be called from a build script (build.rs) only.
+pub fn defer_validation() {
+    DEFERRED.store(true, Ordering::Relaxed);
+}
+
+/// Whether `defer_validation` has been called.
+pub fn validation_deferred() -> bool {
+    DEFERRED.load(Ordering::Relaxed)
+}
+
+/// Panic if the symbol `id` was already written to `path_str` by this run of the build script, as writing
+/// it again would silently replace it.
+///
+/// A symbol whose code failed to parse (see [`defer_validation`]) may be written again, to correct it.
+/// Files left in `OUT_DIR` by previous runs are replaced as usual.
+pub fn check_unwritten(id: &str, path_str: &str) {
+    let path = PathBuf::from(path_str);
+    let written = WRITTEN.lock().unwrap_or_else(PoisonError::into_inner);
+    let duplicate = written.as_ref().is_some_and(|w| w.contains_key(&path));
+    drop(written);
+    let valid = || {
+        crate::transaction::read_file(&path).is_ok_and(|code| parse_items_or_expr(&code).is_ok())
+    };
+    if duplicate && valid() {
+        panic!(
+            "Couldn't write {}: it has already been written by this run of the build script",
+            id
+        );
+    }
+}
+
+/// Record that the symbol `id` was written to `path_str`, for checking by `validate_all`.
+pub fn record_written(id: &str, path_str: &str) {
+    let mut written = WRITTEN.lock().unwrap_or_else(PoisonError::into_inner);
+    written
+        .get_or_insert_with(HashMap::new)
+        .insert(PathBuf::from(path_str), id.to_string());
+}
+
+/// Forget that a file was written, as the write was rolled back by `transaction`.
+pub fn forget_written(path: &Path) {
+    let mut written = WRITTEN.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(written) = written.as_mut() {
+        written.remove(path);
+    }
+}
+
+// Most files hold items, but some (such as struct initialisation expressions) hold an expression.
+fn parse_items_or_expr(code: &str) -> syn::Result<()> {
+    match syn::parse_file(code) {
+        Ok(_) => Ok(()),
+        Err(e) => syn::parse_str::<syn::Expr>(code).map(|_| ()).map_err(|_| e),
+    }
+}
+
+/// Parse every symbol written by this run of the build script, reporting all parse errors at once.
+///
+/// Panics, listing each symbol whose generated code fails to parse along with the error and the file
+/// holding the code, so that the problems surface before the main crate compiles. Call it at the end
+/// of build.rs, after [`defer_validation`] at the start.
+///
+/// ## Further notes
+/// * Must be called from a build script (build.rs) only.
+///
+/// ## Example
+/// build.rs
+/// ```no_run
+/// use rustifact::ToTokenStream;
+///
+/// fn main() {
+///     rustifact::defer_validation();
+///     rustifact::write_const!(WIDTH, u32, &640u32);
+///     rustifact::write_const!(HEIGHT, u32, &480u32);
+///     rustifact::validate_all();
+/// }
+/// ```
+pub fn validate_all() {
+    let mut written: Vec<(PathBuf, String)> = WRITTEN
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+        .unwrap_or_default()
+        .into_iter()
+        .collect();
+    written.sort_by(|(_, a), (_, b)| a.cmp(b));
+    let mut errors = Vec::new();
+    for (path, id) in written {
+        let result = crate::transaction::read_file(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|code| parse_items_or_expr(&code).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            errors.push(format!("  {}: '{}' (in {})", id, e, path.display()));
+        }
+    }
+    if !errors.is_empty() {
+        panic!(
+            "Failed to parse {} generated symbol(s):\n{}",
+            errors.len(),
+            errors.join("\n")
+        );
+    }
+}
@@ -0,0 +1,168 @@
+use crate::tokens::ToTokenStream;
+use proc_macro2::TokenStream;
+use quote::quote;
+use std::path::Path;
+
+/// A builder for writing symbols from plain Rust code, rather than via the `write_`... macros.
+///
+/// Symbols are collected by the builder's methods, then written by `finish`, each to its own file, exactly
+/// as the corresponding macro would write it. This suits build scripts that choose the kinds of symbols
+/// to write at runtime, or that loop over many symbols.
+///
+/// ## Example
+/// build.rs
+/// ```no_run
+/// use rustifact::Writer;
+///
+/// fn main() {
+///     let primes = [2u32, 3, 5, 7];
+///     Writer::new()
+///         .const_("NUM_PRIMES", "usize", &primes.len())
+///         .static_("PRIMES", "[u32; 4]", &primes)
+///         .fn_("prime_names", "Vec<&'static str>", &vec!["two", "three", "five", "seven"])
+///         .finish();
+/// }
+/// ```
+///
+/// src/main.rs
+/// ```no_run
+/// rustifact::use_symbols!(NUM_PRIMES, PRIMES, prime_names);
+/// // The above line is equivalent to the declarations:
+/// // const NUM_PRIMES: usize = 4usize;
+/// // static PRIMES: [u32; 4] = [2u32, 3u32, 5u32, 7u32];
+/// // fn prime_names() -> Vec<&'static str> { vec!["two", "three", "five", "seven"] }
+/// ```
+///
+/// ## Further notes
+/// * Must be called from a build script (build.rs) only.
+#[derive(Default)]
+pub struct Writer {
+    symbols: Vec<(String, TokenStream)>,
+}
+
+impl Writer {
+    pub fn new() -> Writer {
+        Writer::default()
+    }
+
+    /// Add a constant, as written by `write_const!`.
+    ///
+    /// Panics if `t` can't be parsed as a type, or `data` can't be constructed in a const context.
+    pub fn const_<T: ToTokenStream>(mut self, id: &str, t: &str, data: T) -> Writer {
+        crate::internal::check_const_safe(id, &data);
+        let (id_toks, t) = (
+            crate::internal::parse_ident(id),
+            crate::internal::parse_type(t),
+        );
+        let data_toks = data.to_tok_stream();
+        self.symbols
+            .push((id.to_string(), quote! { const #id_toks: #t = #data_toks; }));
+        self
+    }
+
+    /// Add a static variable, as written by `write_static!`.
+    ///
+    /// Panics if `t` can't be parsed as a type.
+    pub fn static_<T: ToTokenStream>(mut self, id: &str, t: &str, data: T) -> Writer {
+        let (id_toks, t) = (
+            crate::internal::parse_ident(id),
+            crate::internal::parse_type(t),
+        );
+        let data_toks = data.to_tok_stream();
+        self.symbols
+            .push((id.to_string(), quote! { static #id_toks: #t = #data_toks; }));
+        self
+    }
+
+    /// Add a getter function, as written by `write_fn!`.
+    ///
+    /// Panics if `t` can't be parsed as a type.
+    pub fn fn_<T: ToTokenStream>(mut self, id: &str, t: &str, data: T) -> Writer {
+        let (id_toks, t) = (
+            crate::internal::parse_ident(id),
+            crate::internal::parse_type(t),
+        );
+        let data_toks = data.to_tok_stream();
+        self.symbols.push((
+            id.to_string(),
+            quote! { fn #id_toks() -> #t { #data_toks } },
+        ));
+        self
+    }
+
+    /// Add arbitrary items, as written by `write_raw!`.
+    pub fn items(mut self, id: &str, tokens: TokenStream) -> Writer {
+        crate::internal::parse_ident(id);
+        self.symbols.push((id.to_string(), tokens));
+        self
+    }
+
+    /// Write the symbols, making them available for import into the main crate via `use_symbols`.
+    pub fn finish(self) {
+        for (id, tokens) in self.symbols {
+            let path_str = crate::internal::path_from_id(&id);
+            write_tokens(&id, &path_str, &tokens, true);
+        }
+    }
+}
+
+/// The path of the file holding the symbol written to `path_str`, for import under an alias.
+pub fn alias_path(path_str: &str) -> String {
+    format!("{}.alias", path_str)
+}
+
+// A symbol imported under an alias is included into a hidden module, so it's written a second time,
+// visible to the module's parent. Nobody reads this copy, so it isn't pretty-printed.
+fn write_alias(path_str: &str, mut syntax_tree: syn::File) {
+    crate::lint::make_parent_visible(&mut syntax_tree);
+    let tokens_str = quote! { #syntax_tree }.to_string();
+    crate::transaction::write_file(alias_path(path_str), tokens_str).unwrap();
+}
+
+/// Format `tokens`, and write them to `path_str` as the symbol `id`.
+///
+/// Symbols written with private visibility (that is, for import via `use_symbols!`) are recorded for
+/// import by `use_all_symbols!`, and written again for import under an alias.
+pub fn write_tokens<T: ToString + ?Sized>(id: &str, path_str: &str, tokens: &T, private: bool) {
+    crate::stub::write_stubs();
+    crate::validate::check_unwritten(id, path_str);
+    let path = Path::new(path_str);
+    let tokens_str = tokens.to_string();
+    match syn::parse_file(&tokens_str) {
+        Ok(mut syntax_tree) => {
+            crate::lint::allow_lints(&mut syntax_tree);
+            // Pretty-printing large outputs is slow, and rarely helpful, so we skip it.
+            if crate::internal::format_disabled()
+                || tokens_str.len() > crate::internal::format_max_bytes()
+            {
+                let tokens_str = quote! { #syntax_tree }.to_string();
+                crate::transaction::write_file(path, tokens_str).unwrap();
+            } else {
+                let formatted = prettyplease::unparse(&syntax_tree);
+                crate::transaction::write_file(path, formatted).unwrap();
+            }
+            if private {
+                crate::manifest::record_symbol(path_str);
+                write_alias(path_str, syntax_tree);
+            }
+        }
+        Err(e) => {
+            crate::transaction::write_file(path, &tokens_str).unwrap();
+            if private {
+                crate::manifest::record_symbol(path_str);
+            }
+            // Reported, along with any other parse errors, by validate_all.
+            if !crate::validate::validation_deferred() {
+                panic!(
+                    "Failed to pretty-print {} due to parse error: '{}'
+This _probably_ indicates in issue with a ToTokenStream implementation. Unformatted output has
+been written to {}",
+                    id,
+                    e,
+                    path.display()
+                );
+            }
+        }
+    }
+    crate::validate::record_written(id, path_str);
+}
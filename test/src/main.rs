@@ -2,6 +2,8 @@ use std::fs::{self, File};
 use std::io::{self, BufRead, Write};
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::thread;
+use std::time::Duration;
 use walkdir::WalkDir;
 
 const TEST_PACKAGE_NAME: &'static str = "test";
@@ -20,6 +22,20 @@ fn main() {
             run_test(path, &output_dir);
         }
     }
+    run_rebuild_test(
+        Path::new("./rebuild/track_file.rebuild"),
+        &output_dir,
+        "data/input.txt",
+        "after",
+        None,
+    );
+    run_rebuild_test(
+        Path::new("./rebuild/stub_src.rebuild"),
+        &output_dir,
+        "src/main.rs",
+        "rustifact::use_symbols!(INPUT, MISSING);\nfn main() {}\n",
+        Some("Symbol MISSING hasn't been written by the build script"),
+    );
 }
 
 fn run_test(input_path: &Path, output_dir: &Path) {
@@ -34,28 +50,93 @@ fn run_test(input_path: &Path, output_dir: &Path) {
         .status()
         .expect("failed to clean test package");
     // Prepare the output dir with the files specified in the file at input_path
-    if !parse_and_write_files(input_path, &output_dir).is_ok() {
-        panic!("Failed to create files for test {}", input_path.display());
+    let expected_error = match parse_and_write_files(input_path, &output_dir) {
+        Ok(expected_error) => expected_error,
+        Err(_) => panic!("Failed to create files for test {}", input_path.display()),
+    };
+    let passed = if let Some(expected_error) = expected_error {
+        // The test passes only if the build fails with the expected error message.
+        let cargo_build_output = Command::new("cargo")
+            .arg("build")
+            .arg("-q")
+            .current_dir(&output_dir)
+            .output()
+            .expect("failed to run test with 'cargo build'");
+        let stderr = String::from_utf8_lossy(&cargo_build_output.stderr);
+        !cargo_build_output.status.success() && stderr.contains(&expected_error)
+    } else {
+        Command::new("cargo")
+            .arg("run")
+            .arg("-q")
+            .current_dir(&output_dir)
+            .status()
+            .expect("failed to run test with 'cargo run'")
+            .success()
+    };
+
+    if passed {
+        println!("***** {} PASS", input_path.display());
+    } else {
+        println!("***** {} FAIL", input_path.display());
     }
-    let cargo_run_output = Command::new("cargo")
-        .arg("run")
-        .arg("-q")
+}
+
+/// Build and run a test twice, modifying one of its input files in between. The test passes if
+/// both runs succeed, and their outputs differ (i.e. the change was picked up by the rebuild). If an
+/// error is expected, the test instead passes if the second run fails with that error.
+fn run_rebuild_test(
+    input_path: &Path,
+    output_dir: &Path,
+    data_path: &str,
+    new_data: &str,
+    expected_error: Option<&str>,
+) {
+    Command::new("cargo")
+        .arg("clean")
+        .arg("-p")
+        .arg(TEST_PACKAGE_NAME)
         .current_dir(&output_dir)
         .status()
-        .expect("failed to run test with 'cargo run'");
+        .expect("failed to clean test package");
+    if !parse_and_write_files(input_path, &output_dir).is_ok() {
+        panic!("Failed to create files for test {}", input_path.display());
+    }
+    let run = || {
+        Command::new("cargo")
+            .arg("run")
+            .arg("-q")
+            .current_dir(&output_dir)
+            .output()
+            .expect("failed to run test with 'cargo run'")
+    };
+    let first = run();
+    // Ensure the modification time of the data file visibly changes.
+    thread::sleep(Duration::from_secs(1));
+    fs::write(output_dir.join(data_path), new_data).expect("failed to modify data file");
+    let second = run();
+    let second_passed = match expected_error {
+        Some(expected_error) => {
+            !second.status.success()
+                && String::from_utf8_lossy(&second.stderr).contains(expected_error)
+        }
+        None => second.status.success() && first.stdout != second.stdout,
+    };
 
-    if cargo_run_output.success() {
+    if first.status.success() && second_passed {
         println!("***** {} PASS", input_path.display());
     } else {
         println!("***** {} FAIL", input_path.display());
     }
 }
 
-fn parse_and_write_files(source_path: &Path, out_prefix: &Path) -> io::Result<()> {
+/// Write the files specified in the file at source_path. Returns the expected build error, if
+/// specified by an `//expect-error:` line preceding the files.
+fn parse_and_write_files(source_path: &Path, out_prefix: &Path) -> io::Result<Option<String>> {
     let source_file = File::open(source_path)?;
     let reader = io::BufReader::new(source_file);
 
     let mut current_file: Option<File> = None;
+    let mut expected_error: Option<String> = None;
 
     for line in reader.lines() {
         let line = line?;
@@ -82,6 +163,12 @@ fn parse_and_write_files(source_path: &Path, out_prefix: &Path) -> io::Result<()
             }
             // Create the file
             current_file = Some(File::create(&file_path)?);
+        } else if line.starts_with("//expect-error:") && current_file.is_none() {
+            expected_error = Some(
+                line.trim_start_matches("//expect-error:")
+                    .trim()
+                    .to_string(),
+            );
         } else if let Some(file) = current_file.as_mut() {
             writeln!(file, "{}", line)?;
         }
@@ -92,5 +179,5 @@ fn parse_and_write_files(source_path: &Path, out_prefix: &Path) -> io::Result<()
         file.flush()?;
     }
 
-    Ok(())
+    Ok(expected_error)
 }